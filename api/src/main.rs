@@ -1,5 +1,6 @@
 mod puzzle_dto;
 
+use std::sync::Arc;
 use axum::http::{Method, StatusCode};
 use axum::{extract, Json, Router};
 use axum::routing::{get, post};
@@ -8,24 +9,56 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
-use db::{initialize_db, insert_puzzle, get_puzzle_by_id, PuzzleDAO, PuzzleDTO};
-use generator::generate_board;
+use db::{initialize_db, insert_puzzle, get_puzzle_by_id, get_solution_attempts, record_solution_attempt, PuzzleDAO, PuzzleDTO};
+use types::{next_hint, Board, Hint, HintLevel, PuzzleFile, PuzzleMetadata, Selection, MAX_SOLUTION_ATTEMPTS};
+use crate::puzzle_dto::puzzle_dto_to_board;
+use generator::{auto_grid_size, generate_board_from_catalogue, generate_board_with_required_words, random_seed, rng_from_seed, Alphabet, AutoSize, BoardShape, Catalogue, Difficulty, GenerationError, GeneratorOptions, DICTIONARY_DIRECTORY, MAX_GRID_SIDE, MIN_GRID_SIDE};
 
 async fn not_found() -> (StatusCode, Json<serde_json::Value>) {
     (
         StatusCode::NOT_FOUND,
         Json(json!({
             "get_board": "/api/{board_id}",
-            "create_board": "/api/g"
+            "create_board": "/api/g",
+            "create_themed_board": "/api/g/themed",
+            "export_board": "/api/{board_id}/export",
+            "import_board": "/api/import",
+            "validate_selection": "/api/{board_id}/validate",
+            "hint": "/api/{board_id}/hint",
+            "guess_solution": "/api/{board_id}/guess"
         }))
     )
 }
 
 #[derive(Deserialize)]
 pub struct CreateBoardParams {
+    // may be left out when auto_size is requested
+    rows: Option<usize>,
+    cols: Option<usize>,
+    message: String,
+    auto_size: Option<bool>,
+    target_words: Option<usize>,
+    aspect_ratio: Option<f32>,
+    seed: Option<u64>,
+    difficulty: Option<String>,
+    language: Option<String>,
+    category: Option<String>,
+    shape: Option<String>
+}
+
+#[derive(Deserialize)]
+pub struct CreateThemedBoardParams {
     rows: usize,
     cols: usize,
-    message: String
+    message: String,
+    words: Vec<String>,
+    // fill the rest of the grid from the dictionary, otherwise words have to cover it on their own
+    top_up: Option<bool>,
+    seed: Option<u64>,
+    difficulty: Option<String>,
+    language: Option<String>,
+    category: Option<String>,
+    shape: Option<String>
 }
 
 #[derive(Serialize)]
@@ -35,23 +68,155 @@ pub struct ErrorResponse {
 }
 
 #[derive(Serialize)]
-pub struct CreateResponse {
+pub struct ImportResponse {
     id: String
 }
 
-pub fn create_board(
+#[derive(Deserialize)]
+pub struct PreviousHint {
+    word: String,
+    level: HintLevel
+}
+
+// The service keeps no sessions, so the client sends what it found and the last hint it got
+#[derive(Deserialize)]
+pub struct HintParams {
+    #[serde(default)]
+    found_words: Vec<String>,
+    previous: Option<PreviousHint>
+}
+
+#[derive(Deserialize)]
+pub struct GuessParams {
+    // chosen by the client and kept between visits, attempts are counted per player and board
+    player: String,
+    guess: String
+}
+
+#[derive(Serialize)]
+pub struct GuessResponse {
+    correct: bool,
+    attempts_left: usize,
+    // revealed only after a correct guess
+    solution: Option<String>
+}
+
+#[derive(Serialize)]
+pub struct ValidateResponse {
+    word: String
+}
+
+#[derive(Serialize)]
+pub struct CreateResponse {
+    id: String,
+    seed: u64,
+    rows: usize,
+    cols: usize
+}
+
+fn bad_request(message: String) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            status: 400,
+            message
+        })
+    )
+}
+
+fn check_grid_size(rows: usize, cols: usize) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let sides = MIN_GRID_SIDE..=MAX_GRID_SIDE;
+    if !sides.contains(&rows) || !sides.contains(&cols) {
+        return Err(bad_request(format!("rows and cols have to be between {} and {}", MIN_GRID_SIDE, MAX_GRID_SIDE)));
+    }
+
+    return Ok(());
+}
+
+// Generation is CPU bound, so it runs on the blocking pool instead of stalling the async workers
+async fn generate_off_runtime<F>(generate: F) -> Result<Board, (StatusCode, Json<ErrorResponse>)>
+where
+    F: FnOnce() -> Result<Board, GenerationError> + Send + 'static
+{
+    match tokio::task::spawn_blocking(generate).await {
+        Ok(Ok(board)) => Ok(board),
+        Ok(Err(error)) => Err(bad_request(error.to_string())),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                status: 500,
+                message: "Board generation did not finish".to_string()
+            })
+        ))
+    }
+}
+
+fn generator_options(difficulty: Option<String>, language: Option<String>, category: Option<String>, shape: Option<String>) -> Result<GeneratorOptions, (StatusCode, Json<ErrorResponse>)> {
+    let mut options = GeneratorOptions::default();
+    if let Some(difficulty) = difficulty {
+        match difficulty.parse::<Difficulty>() {
+            Ok(difficulty) => options.policy = difficulty.policy(),
+            Err(error) => return Err(bad_request(error))
+        }
+    }
+
+    if let Some(language) = language {
+        match Alphabet::for_language(&language) {
+            Some(alphabet) => options.alphabet = alphabet,
+            None => return Err(bad_request(format!("Language {} is not supported", language)))
+        }
+    }
+    options.category = category;
+
+    if let Some(shape) = shape {
+        match shape.parse::<BoardShape>() {
+            Ok(shape) => options.shape = shape,
+            Err(error) => return Err(bad_request(error))
+        }
+    }
+
+    return Ok(options);
+}
+
+pub async fn create_board(
+    extract::State(catalogue): extract::State<Arc<Catalogue>>,
     extract::Json(
-        CreateBoardParams {rows, cols, message}
+        CreateBoardParams {rows, cols, message, auto_size, target_words, aspect_ratio, seed, difficulty, language, category, shape}
     ): extract::Json<CreateBoardParams>
 ) -> Result<Json<CreateResponse>, (StatusCode, Json<ErrorResponse>)>  {
-    let board = generate_board(rows, cols, message.clone());
+    let seed = seed.unwrap_or_else(random_seed);
+
+    let options = generator_options(difficulty, language, category, shape)?;
+
+    let (rows, cols) = match (rows, cols, auto_size.unwrap_or(false)) {
+        (Some(rows), Some(cols), false) => {
+            check_grid_size(rows, cols)?;
+            (rows, cols)
+        },
+        (_, _, false) => return Err(bad_request("rows and cols are required unless auto_size is requested".to_string())),
+        (_, _, true) => {
+            let mut sizing = AutoSize::default();
+            sizing.target_words = target_words.unwrap_or(sizing.target_words);
+            sizing.aspect_ratio = aspect_ratio;
 
-    let board_id = insert_puzzle(board);
+            match auto_grid_size(&message, &sizing, &options) {
+                Ok(size) => size,
+                Err(error) => return Err(bad_request(error.to_string()))
+            }
+        }
+    };
+
+    let language = options.alphabet.language.clone();
+    let board = generate_off_runtime(move || {
+        generate_board_from_catalogue(rows, cols, message, &catalogue, &options, &mut rng_from_seed(seed))
+    }).await?;
+
+    let board_id = insert_puzzle(board, Some(language));
 
     if (board_id.is_ok()) {
         return Ok(
             Json(CreateResponse {
-                id: board_id.unwrap()
+                id: board_id.unwrap(), seed, rows, cols
             })
         );
     } else {
@@ -65,6 +230,42 @@ pub fn create_board(
     }
 }
 
+pub async fn create_themed_board(
+    extract::State(catalogue): extract::State<Arc<Catalogue>>,
+    extract::Json(
+        CreateThemedBoardParams {rows, cols, message, words, top_up, seed, difficulty, language, category, shape}
+    ): extract::Json<CreateThemedBoardParams>
+) -> Result<Json<CreateResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if words.is_empty() {
+        return Err(bad_request("Themed board needs at least one word".to_string()));
+    }
+
+    check_grid_size(rows, cols)?;
+
+    let seed = seed.unwrap_or_else(random_seed);
+    let options = generator_options(difficulty, language, category, shape)?;
+
+    let filler_words: Option<Vec<String>> = if top_up.unwrap_or(true) {
+        let language = options.alphabet.language.as_str();
+        match catalogue.select(language, options.category.as_deref()) {
+            Ok(filler) => Some(filler),
+            Err(error) => return Err(bad_request(error.to_string()))
+        }
+    } else {
+        None
+    };
+
+    let language = options.alphabet.language.clone();
+    let board = generate_off_runtime(move || {
+        generate_board_with_required_words(rows, cols, message, &words, filler_words.as_ref(), &options, &mut rng_from_seed(seed))
+    }).await?;
+
+    match insert_puzzle(board, Some(language)) {
+        Ok(board_id) => Ok(Json(CreateResponse { id: board_id, seed, rows, cols })),
+        Err(_) => Err(bad_request("There was error processing request".to_string()))
+    }
+}
+
 pub async fn get_board_by_id (
     extract::Path(board_id): extract::Path<String>
 ) -> Result<Json<PuzzleDTO>, (StatusCode, Json<ErrorResponse>)> {
@@ -98,6 +299,110 @@ pub async fn get_board_by_id (
     );
 }
 
+pub async fn export_board_by_id (
+    extract::Path(board_id): extract::Path<String>
+) -> Result<Json<PuzzleFile>, (StatusCode, Json<ErrorResponse>)> {
+    let puzzle_dto = match get_puzzle_by_id(board_id) {
+        Ok(puzzle_dto) => puzzle_dto,
+        Err(error) => return Err(bad_request(error))
+    };
+
+    let metadata = PuzzleMetadata {
+        id: Some(puzzle_dto.id.clone()),
+        created_date: Some(puzzle_dto.created_date.clone()),
+        ..PuzzleMetadata::default()
+    };
+
+    // puzzles stored before the language was kept leave it open in the file
+    let language = puzzle_dto.language.clone();
+    let puzzle_file = puzzle_dto_to_board(puzzle_dto)
+        .map_err(|error| bad_request(error.to_string()))
+        .and_then(|board| PuzzleFile::from_board(&board, language, metadata).map_err(|error| bad_request(error.to_string())))?;
+
+    return Ok(Json(puzzle_file));
+}
+
+// Body is read as text so the format version is checked before the rest of the file
+pub async fn import_board(body: String) -> Result<Json<ImportResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (board, language) = match PuzzleFile::from_json(&body).and_then(|puzzle_file| Ok((puzzle_file.to_board()?, puzzle_file.language))) {
+        Ok(imported) => imported,
+        Err(error) => return Err(bad_request(error.to_string()))
+    };
+
+    match insert_puzzle(board, language) {
+        Ok(board_id) => Ok(Json(ImportResponse { id: board_id })),
+        Err(_) => Err(bad_request("There was error processing request".to_string()))
+    }
+}
+
+// Checks the selected start and end cell against the stored placements, either end may come first
+pub async fn validate_selection(
+    extract::Path(board_id): extract::Path<String>,
+    Json(selection): Json<Selection>
+) -> Result<Json<ValidateResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let puzzle_dto = match get_puzzle_by_id(board_id) {
+        Ok(puzzle_dto) => puzzle_dto,
+        Err(error) => return Err(bad_request(error))
+    };
+
+    return match selection.find_placement(&puzzle_dto.placements) {
+        Ok(placement) => Ok(Json(ValidateResponse { word: placement.word.clone() })),
+        Err(error) => Err(bad_request(error.to_string()))
+    };
+}
+
+pub async fn get_hint(
+    extract::Path(board_id): extract::Path<String>,
+    Json(params): Json<HintParams>
+) -> Result<Json<Hint>, (StatusCode, Json<ErrorResponse>)> {
+    let puzzle_dto = match get_puzzle_by_id(board_id) {
+        Ok(puzzle_dto) => puzzle_dto,
+        Err(error) => return Err(bad_request(error))
+    };
+
+    let previous = params.previous.as_ref().map(|hint| (hint.word.as_str(), hint.level));
+
+    return match next_hint(&puzzle_dto.placements, &params.found_words, previous) {
+        Some(hint) => Ok(Json(hint)),
+        None => Err(bad_request("All words were already found".to_string()))
+    };
+}
+
+// The attempt is stored before the answer goes out, so a guess that could not be counted is not answered either
+pub async fn guess_solution(
+    extract::Path(board_id): extract::Path<String>,
+    Json(params): Json<GuessParams>
+) -> Result<Json<GuessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if params.player.trim().is_empty() {
+        return Err(bad_request("Player is required to guess the solution".to_string()));
+    }
+
+    let puzzle_dto = match get_puzzle_by_id(board_id.clone()) {
+        Ok(puzzle_dto) => puzzle_dto,
+        Err(error) => return Err(bad_request(error))
+    };
+
+    let previous_attempts = get_solution_attempts(&board_id, &params.player);
+    if previous_attempts.unwrap_or(0) as usize >= MAX_SOLUTION_ATTEMPTS {
+        return Err(bad_request(format!("All {} attempts to guess the solution were used", MAX_SOLUTION_ATTEMPTS)));
+    }
+
+    // puzzles stored before the language was kept were all generated with English letters
+    let alphabet = puzzle_dto.language.as_deref().and_then(Alphabet::for_language).unwrap_or_else(Alphabet::english);
+    let guess = alphabet.normalize_message(&params.guess);
+    let correct = !guess.is_empty() && guess == puzzle_dto.solution;
+
+    let attempts = match record_solution_attempt(&board_id, &params.player, previous_attempts) {
+        Ok(attempts) => attempts as usize,
+        Err(_) => return Err(bad_request("Guess could not be counted, try again".to_string()))
+    };
+
+    return Ok(Json(GuessResponse {
+        correct,
+        attempts_left: MAX_SOLUTION_ATTEMPTS.saturating_sub(attempts),
+        solution: if correct { Some(puzzle_dto.original_message) } else { None }
+    }));
+}
 
 #[tokio::main]
 async fn main() {
@@ -117,10 +422,21 @@ async fn main() {
         ])
         .allow_private_network(true);
 
+    // dictionaries do not change while the server runs, so they are read only once
+    let catalogue = Catalogue::from_directory(DICTIONARY_DIRECTORY, None)
+        .expect("dictionary catalogue could not be loaded");
+
     let app = Router::new()
         .route("/api/{board_id}", get(get_board_by_id))
         .route("/api/g", post(create_board))
+        .route("/api/g/themed", post(create_themed_board))
+        .route("/api/{board_id}/export", get(export_board_by_id))
+        .route("/api/import", post(import_board))
+        .route("/api/{board_id}/validate", post(validate_selection))
+        .route("/api/{board_id}/hint", post(get_hint))
+        .route("/api/{board_id}/guess", post(guess_solution))
         .fallback(not_found)
+        .with_state(Arc::new(catalogue))
         .layer(TraceLayer::new_for_http())
         .layer(cors_layer_restrictions)
         ;
@@ -1,34 +1,33 @@
 use std::collections::HashSet;
 use db::{PuzzleDAO, PuzzleDTO};
-use types::Board;
+use types::{from_grid_string, Board, GridError};
 
-pub fn puzzle_dto_to_board(puzzle: PuzzleDTO) -> Board {
-    let mut grid: Vec<Vec<char>> = Vec::new();
-    let mut peeked = puzzle.grid.chars().peekable();
-
-    while peeked.peek().is_some() {
-        let row: String = peeked.by_ref().take(puzzle.rows as usize).collect();
-        let cols: Vec<char> = row.chars().map(|c|c).collect();
-
-        grid.push(cols)
-    }
+pub fn puzzle_dto_to_board(puzzle: PuzzleDTO) -> Result<Board, GridError> {
+    let grid = from_grid_string(puzzle.rows, puzzle.cols, &puzzle.grid, &puzzle.solution_cells)?;
 
     let mut words = HashSet::new();
     for word in puzzle.history {
         words.insert(word);
     }
 
-    return Board {
+    return Ok(Board {
         rows: puzzle.rows, cols: puzzle.cols,
         solution: puzzle.solution, message: puzzle.original_message,
-        grid, words
-    };
+        grid, words, placements: puzzle.placements, solution_cells: puzzle.solution_cells
+    });
 }
 
-pub fn puzzle_dto_to_puzzle_dao(puzzle_dto: PuzzleDTO) -> PuzzleDAO {
-    return PuzzleDAO {
+pub fn puzzle_dto_to_puzzle_dao(puzzle_dto: PuzzleDTO) -> Result<PuzzleDAO, String> {
+    // a puzzle stored without its placements could no longer validate any selection
+    let placements: String = serde_json::to_string(&puzzle_dto.placements).map_err(|error| error.to_string())?;
+
+    return Ok(PuzzleDAO {
         rows: puzzle_dto.rows as u64, cols: puzzle_dto.cols as u64,
         solution: puzzle_dto.solution, original_message: puzzle_dto.original_message,
-        grid: puzzle_dto.grid, history: puzzle_dto.history, id: puzzle_dto.id, created_date: puzzle_dto.created_date
-    };
+        grid: puzzle_dto.grid, history: puzzle_dto.history, id: puzzle_dto.id, created_date: puzzle_dto.created_date,
+        solution_cells: puzzle_dto.solution_cells.iter().map(|(row, col)| (row * puzzle_dto.cols + col) as u64).collect(),
+        blocked_cells: puzzle_dto.blocked_cells.iter().map(|(row, col)| (row * puzzle_dto.cols + col) as u64).collect(),
+        placements,
+        language: puzzle_dto.language.unwrap_or_default()
+    });
 }
@@ -2,9 +2,18 @@ mod puzzle_dao;
 
 use serde::Serialize;
 use skytable::{ClientResult, Config, Connection, Pipeline, query};
-use types::Board;
+use types::{Board, Placement};
 use crate::puzzle_dao::{board_to_puzzle_dao,  puzzle_dao_to_board, puzzle_dao_to_puzzle_dto, PuzzleDAO};
 
+// Columns added to cruciwordo.puzzle after it was first created. CREATE MODEL IF NOT EXISTS leaves an existing
+// model untouched, so they are added here one by one. Puzzles stored before a column existed hold null in it
+const PUZZLE_MODEL_MIGRATIONS: [(&str, &str); 4] = [
+    ("solution_cells", "ALTER MODEL cruciwordo.puzzle ADD solution_cells { type: list { type: uint64 }, nullable: true }"),
+    ("blocked_cells", "ALTER MODEL cruciwordo.puzzle ADD blocked_cells { type: list { type: uint64 }, nullable: true }"),
+    ("placements", "ALTER MODEL cruciwordo.puzzle ADD placements { type: string, nullable: true }"),
+    ("language", "ALTER MODEL cruciwordo.puzzle ADD language { type: string, nullable: true }"),
+];
+
 pub fn initialize_db() {
 
     let create_space = query!(
@@ -12,10 +21,15 @@ pub fn initialize_db() {
     );
 
     let create_model = query!(
-        "CREATE MODEL IF NOT EXISTS cruciwordo.puzzle (primary id: string, created_date: string, rows: uint64, cols: uint64, original_message: string, solution: string, grid: string, history: list {type: string})"
+        "CREATE MODEL IF NOT EXISTS cruciwordo.puzzle (primary id: string, created_date: string, rows: uint64, cols: uint64, original_message: string, solution: string, grid: string, history: list {type: string}, solution_cells: list {type: uint64}, blocked_cells: list {type: uint64}, placements: string, language: string)"
+    );
+
+    // one row per player and puzzle, the id joins both so a player's count is a single lookup
+    let create_attempts_model = query!(
+        "CREATE MODEL IF NOT EXISTS cruciwordo.solution_attempt (primary id: string, puzzle_id: string, player: string, attempts: uint64)"
     );
 
-    let init_database = Pipeline::new().add(&create_space).add(&create_model);
+    let init_database = Pipeline::new().add(&create_space).add(&create_model).add(&create_attempts_model);
 
     let db_conn = get_database();
 
@@ -30,6 +44,26 @@ pub fn initialize_db() {
     } else {
         println!("is OK")
     }
+
+    migrate_db();
+}
+
+// Brings a model created by an older version up to date. Columns the model already has are reported and skipped
+pub fn migrate_db() {
+    let mut db_conn = match get_database() {
+        Ok(db_conn) => db_conn,
+        Err(error) => {
+            println!("Migration could not connect {}", error.to_string());
+            return;
+        }
+    };
+
+    for (column, migration) in PUZZLE_MODEL_MIGRATIONS {
+        match db_conn.query_parse::<()>(&query!(migration)) {
+            Ok(_) => println!("Added column {}", column),
+            Err(error) => println!("Column {} was not added {}", column, error.to_string())
+        }
+    }
 }
 
 pub fn get_database () -> ClientResult<Connection> {
@@ -50,13 +84,14 @@ pub fn get_database () -> ClientResult<Connection> {
     return db;
 }
 
-pub fn insert_puzzle(board: Board) -> Result<String, String> {
-    let puzzle_dao: PuzzleDAO = board_to_puzzle_dao(board);
+// Language of the board's alphabet, None when it is not known, e.g. for an imported file without one
+pub fn insert_puzzle(board: Board, language: Option<String>) -> Result<String, String> {
+    let puzzle_dao: PuzzleDAO = board_to_puzzle_dao(board, language)?;
 
     let mut db = get_database();
 
     let mut insert_query = query!(
-        "insert into cruciwordo.puzzle ( ?, ?, ?, ?, ?, ?, ?, ? )",
+        "insert into cruciwordo.puzzle ( ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ? )",
         &puzzle_dao
     );
 
@@ -80,12 +115,48 @@ pub fn get_puzzle_by_id(puzzle_id: String) -> Result<PuzzleDTO, String> {
     if select_puzzle_status.is_ok() {
         let puzzle_dao = select_puzzle_status.unwrap();
 
-        return Ok(puzzle_dao_to_puzzle_dto(puzzle_dao));
+        return puzzle_dao_to_puzzle_dto(puzzle_dao);
     }
 
     return Err("Problem here".to_string())
 }
 
+fn solution_attempt_id(puzzle_id: &str, player: &str) -> String {
+    return format!("{}:{}", puzzle_id, player);
+}
+
+// None when the player has not guessed the solution of the puzzle yet
+pub fn get_solution_attempts(puzzle_id: &str, player: &str) -> Option<u64> {
+    let mut db = get_database().ok()?;
+    let id = solution_attempt_id(puzzle_id, player);
+    let select_query = query!(
+        "select attempts from cruciwordo.solution_attempt where id = ?", id.as_str()
+    );
+
+    return db.query_parse::<(u64,)>(&select_query).ok().map(|(attempts,)| attempts);
+}
+
+// Counts one more guess. The first guess inserts the row, so a second first guess of the same player fails
+// instead of resetting the count
+pub fn record_solution_attempt(puzzle_id: &str, player: &str, previous_attempts: Option<u64>) -> Result<u64, String> {
+    let mut db = get_database().map_err(|error| error.to_string())?;
+    let id = solution_attempt_id(puzzle_id, player);
+
+    let status = match previous_attempts {
+        Some(_) => db.query_parse::<()>(&query!(
+            "update cruciwordo.solution_attempt set attempts += ? where id = ?", 1u64, id.as_str()
+        )),
+        None => db.query_parse::<()>(&query!(
+            "insert into cruciwordo.solution_attempt ( ?, ?, ?, ? )", id.as_str(), puzzle_id, player, 1u64
+        ))
+    };
+
+    return match status {
+        Ok(_) => Ok(previous_attempts.unwrap_or(0) + 1),
+        Err(error) => Err(error.to_string())
+    };
+}
+
 #[derive(Serialize, Clone)]
 pub struct PuzzleDAO {
     pub id: String,
@@ -95,7 +166,15 @@ pub struct PuzzleDAO {
     pub original_message: String,
     pub solution: String,
     pub grid: String,
-    pub history: Vec<String>
+    pub history: Vec<String>,
+    // row major index (row * cols + col) of every solution letter
+    pub solution_cells: Vec<u64>,
+    // row major index of every cell outside of the board's shape
+    pub blocked_cells: Vec<u64>,
+    // JSON array of the word placements
+    pub placements: String,
+    // empty when the language of the puzzle is not known
+    pub language: String
 }
 
 #[derive(Serialize)]
@@ -107,5 +186,11 @@ pub struct PuzzleDTO {
     pub original_message: String,
     pub solution: String,
     pub grid: String,
-    pub history: Vec<String>
+    pub history: Vec<String>,
+    pub solution_cells: Vec<(usize, usize)>,
+    pub blocked_cells: Vec<(usize, usize)>,
+    // stays on the server, selections are validated against it
+    #[serde(skip_serializing)]
+    pub placements: Vec<Placement>,
+    pub language: Option<String>
 }
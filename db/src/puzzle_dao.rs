@@ -4,8 +4,8 @@ use nanoid::nanoid;
 use serde::Serialize;
 use skytable::ClientResult;
 use skytable::query::{QList, SQParam};
-use skytable::response::FromResponse;
-use types::Board;
+use skytable::response::{FromResponse, RList};
+use types::{from_grid_string, Board, Placement};
 use crate::{PuzzleDAO, PuzzleDTO};
 
 impl SQParam for PuzzleDAO {
@@ -17,27 +17,37 @@ impl SQParam for PuzzleDAO {
             self.original_message.append_param(q) +
             self.solution.append_param(q) +
             self.grid.append_param(q) +
-            QList::new(&self.history).append_param(q)
+            QList::new(&self.history).append_param(q) +
+            QList::new(&self.solution_cells).append_param(q) +
+            QList::new(&self.blocked_cells).append_param(q) +
+            self.placements.append_param(q) +
+            self.language.append_param(q)
     }
 }
 
 impl FromResponse for PuzzleDAO {
     fn from_response(resp: skytable::response::Response) -> ClientResult<Self> {
-        let (id, created_date, rows, cols, original_message, solution, grid, history) = resp.parse::<(String, String, u64, u64, String, String, String, RList<String>)>()?;
+        // columns added by migrate_db are null for puzzles stored before them
+        let (id, created_date, rows, cols, original_message, solution, grid, history, solution_cells, blocked_cells, placements, language) = resp.parse::<(String, String, u64, u64, String, String, String, RList<String>, Option<RList<u64>>, Option<RList<u64>>, Option<String>, Option<String>)>()?;
         Ok (PuzzleDAO {
-            id, created_date, rows, cols, original_message, solution, grid, history: history.into_values()
+            id, created_date, rows, cols, original_message, solution, grid, history: history.into_values(),
+            solution_cells: solution_cells.map(|cells| cells.into_values()).unwrap_or_default(),
+            blocked_cells: blocked_cells.map(|cells| cells.into_values()).unwrap_or_default(),
+            placements: placements.unwrap_or("[]".to_string()),
+            language: language.unwrap_or_default()
         })
     }
 }
 
-pub fn board_to_puzzle_dao(board: Board) -> PuzzleDAO {
+pub fn board_to_puzzle_dao(board: Board, language: Option<String>) -> Result<PuzzleDAO, String> {
     let board_id = nanoid!(10, &nanoid::alphabet::SAFE);
 
-    let grid_string: String = board.grid.iter()
-        .map(|r| r.iter().map(|c| format!("{}", c)).collect::<String>())
-        .rfold(String::from(""), |data, r| format!("{}{}", r, data));
+    let grid_string: String = board.to_grid_string().map_err(|error| error.to_string())?;
+    let placements: String = serde_json::to_string(&board.placements).map_err(|error| error.to_string())?;
 
-    let history: Vec<String> = board.words.iter().map(|w|w).collect();
+    let history: Vec<String> = board.words.iter().cloned().collect();
+    let solution_cells: Vec<u64> = board.solution_cells.iter().map(|(row, col)| (row * board.cols + col) as u64).collect();
+    let blocked_cells: Vec<u64> = board.blocked_cells().iter().map(|(row, col)| (row * board.cols + col) as u64).collect();
 
     let result_puzzle: PuzzleDAO = PuzzleDAO {
         id: board_id.clone(),
@@ -46,37 +56,44 @@ pub fn board_to_puzzle_dao(board: Board) -> PuzzleDAO {
         solution: board.solution,
         grid: grid_string,
         rows: board.rows as u64, cols: board.cols as u64,
-        history,
+        history, solution_cells, blocked_cells, placements,
+        language: language.unwrap_or_default(),
     };
 
-    return result_puzzle;
+    return Ok(result_puzzle);
 }
 
-pub fn puzzle_dao_to_board(puzzle: PuzzleDAO) -> Board {
-    let mut grid: Vec<Vec<char>> = Vec::new();
-    let mut peeked = puzzle.grid.chars().peekable();
-
-    while peeked.peek().is_some() {
-        let row: String = peeked.by_ref().take(puzzle.rows as usize).collect();
-        let cols: Vec<char> = row.chars().map(|c|c).collect();
-
-        grid.push(cols)
-    }
-
+pub fn puzzle_dao_to_board(puzzle: PuzzleDAO) -> Result<Board, String> {
     let mut words = HashSet::new();
     for word in puzzle.history {
         words.insert(word);
     }
 
-    return Board {
+    let solution_cells: Vec<(usize, usize)> = puzzle.solution_cells.iter()
+        .map(|idx| ((idx / puzzle.cols) as usize, (idx % puzzle.cols) as usize))
+        .collect();
+
+    // blocked cells are part of the grid string so they need no extra handling
+    let grid = from_grid_string(puzzle.rows as usize, puzzle.cols as usize, &puzzle.grid, &solution_cells).map_err(|error| error.to_string())?;
+    let placements: Vec<Placement> = serde_json::from_str(&puzzle.placements).map_err(|error| error.to_string())?;
+
+    return Ok(Board {
         rows: puzzle.rows as usize, cols: puzzle.cols as usize,
         grid: grid, solution: puzzle.solution, message: puzzle.original_message,
-        words
-    }
+        words, placements, solution_cells
+    })
 }
 
-pub fn puzzle_dao_to_puzzle_dto(puzzle_dao: PuzzleDAO) -> PuzzleDTO {
-    return PuzzleDTO {
+pub fn puzzle_dao_to_puzzle_dto(puzzle_dao: PuzzleDAO) -> Result<PuzzleDTO, String> {
+    let solution_cells: Vec<(usize, usize)> = puzzle_dao.solution_cells.iter()
+        .map(|idx| ((idx / puzzle_dao.cols) as usize, (idx % puzzle_dao.cols) as usize))
+        .collect();
+
+    // grid is passed on as a string, decoding it only makes sure it matches the stored dimensions
+    from_grid_string(puzzle_dao.rows as usize, puzzle_dao.cols as usize, &puzzle_dao.grid, &solution_cells).map_err(|error| error.to_string())?;
+    let placements: Vec<Placement> = serde_json::from_str(&puzzle_dao.placements).map_err(|error| error.to_string())?;
+
+    return Ok(PuzzleDTO {
         id: puzzle_dao.id,
         solution: puzzle_dao.solution,
         created_date: puzzle_dao.created_date,
@@ -84,6 +101,12 @@ pub fn puzzle_dao_to_puzzle_dto(puzzle_dao: PuzzleDAO) -> PuzzleDTO {
         original_message: puzzle_dao.original_message,
         grid: puzzle_dao.grid,
         history: puzzle_dao.history,
-    };
+        solution_cells,
+        placements,
+        blocked_cells: puzzle_dao.blocked_cells.iter()
+            .map(|idx| ((idx / puzzle_dao.cols) as usize, (idx % puzzle_dao.cols) as usize))
+            .collect(),
+        language: Some(puzzle_dao.language).filter(|language| !language.is_empty()),
+    });
 
 }
\ No newline at end of file
@@ -0,0 +1,172 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CaseFolding {
+    #[default]
+    Upper,
+    Lower,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alphabet {
+    pub language: String,
+    // Letters as written in the grid, every letter has to be a single (NFC composed) char
+    pub letters: Vec<char>,
+    pub case: CaseFolding,
+    // When false Á becomes A, Ž becomes Z etc. and words are matched without accents
+    pub keep_diacritics: bool,
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Alphabet::english()
+    }
+}
+
+impl Alphabet {
+    pub fn new(language: &str, letters: &str, keep_diacritics: bool) -> Self {
+        Alphabet {
+            language: language.to_string(),
+            letters: letters.nfc().collect(),
+            case: CaseFolding::Upper,
+            keep_diacritics,
+        }
+    }
+
+    pub fn english() -> Self {
+        Alphabet::new("en", "ABCDEFGHIJKLMNOPQRSTUVWXYZ", false)
+    }
+
+    pub fn slovak() -> Self {
+        Alphabet::new("sk", "AÁÄBCČDĎEÉFGHIÍJKLĹĽMNŇOÓÔPQRŔSŠTŤUÚVWXYÝZŽ", true)
+    }
+
+    pub fn czech() -> Self {
+        Alphabet::new("cs", "AÁBCČDĎEÉĚFGHIÍJKLMNŇOÓPQRŘSŠTŤUÚŮVWXYÝZŽ", true)
+    }
+
+    pub fn german() -> Self {
+        Alphabet::new("de", "ABCDEFGHIJKLMNOPQRSTUVWXYZÄÖÜß", true)
+    }
+
+    pub fn for_language(language: &str) -> Option<Self> {
+        match language.to_lowercase().as_str() {
+            "en" => Some(Alphabet::english()),
+            "sk" => Some(Alphabet::slovak()),
+            "cs" | "cz" => Some(Alphabet::czech()),
+            "de" => Some(Alphabet::german()),
+            _ => None
+        }
+    }
+
+    pub fn with_diacritics(mut self, keep_diacritics: bool) -> Self {
+        self.keep_diacritics = keep_diacritics;
+        return self;
+    }
+
+    pub fn with_case(mut self, case: CaseFolding) -> Self {
+        self.case = case;
+        return self;
+    }
+
+    // Brings the word into the form used on the grid, None if it has letters outside of the alphabet
+    pub fn normalize_word(&self, word: &str) -> Option<String> {
+        let normalized = self.fold(word.trim());
+
+        if normalized.is_empty() || !normalized.chars().all(|c| self.contains(c)) {
+            return None;
+        }
+
+        return Some(normalized);
+    }
+
+    // Keeps only the letters of the message that can be written into the grid
+    pub fn normalize_message(&self, message: &str) -> String {
+        return self.fold(message).chars().filter(|c| self.contains(*c)).collect();
+    }
+
+    // Compares two texts by the letters they would leave in the grid, so spaces, punctuation and
+    // letter case do not matter
+    pub fn same_message(&self, text: &str, other: &str) -> bool {
+        let normalized = self.normalize_message(text);
+
+        return !normalized.is_empty() && normalized == self.normalize_message(other);
+    }
+
+    fn contains(&self, letter: char) -> bool {
+        return self.letters.iter().any(|l| self.fold_char(*l) == Some(letter));
+    }
+
+    fn fold(&self, text: &str) -> String {
+        let composed: String = if self.keep_diacritics {
+            text.nfc().collect()
+        } else {
+            text.nfd().filter(|c| !is_combining_mark(*c)).nfc().collect()
+        };
+
+        return composed.chars().filter_map(|c| self.fold_char(c)).collect();
+    }
+
+    fn fold_char(&self, letter: char) -> Option<char> {
+        let stripped: char = if self.keep_diacritics {
+            letter
+        } else {
+            letter.to_string().nfd().find(|c| !is_combining_mark(*c))?
+        };
+
+        let folded: String = match self.case {
+            CaseFolding::Upper => stripped.to_uppercase().collect(),
+            CaseFolding::Lower => stripped.to_lowercase().collect(),
+        };
+        let mut folded_chars = folded.chars();
+
+        // letters like ß have no single char upper case form, those stay as they are
+        return match (folded_chars.next(), folded_chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => Some(stripped)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diacritics_are_kept_only_when_the_alphabet_has_them() {
+        assert_eq!(Alphabet::slovak().normalize_word("Žltý"), Some("ŽLTÝ".to_string()));
+        assert_ne!(Alphabet::slovak().normalize_word("Žltý"), Alphabet::slovak().normalize_word("ZLTY"));
+
+        assert_eq!(Alphabet::english().normalize_word("Žltý"), Some("ZLTY".to_string()));
+        assert_eq!(Alphabet::slovak().with_diacritics(false).normalize_word("Žltý"), Some("ZLTY".to_string()));
+    }
+
+    #[test]
+    fn decomposed_input_matches_composed_input() {
+        let decomposed = "Z\u{30C}lty\u{301}";
+
+        assert_eq!(Alphabet::slovak().normalize_word(decomposed), Some("ŽLTÝ".to_string()));
+        assert_eq!(Alphabet::slovak().normalize_word(decomposed).map(|w| w.chars().count()), Some(4));
+        assert_eq!(Alphabet::english().normalize_word(decomposed), Some("ZLTY".to_string()));
+    }
+
+    #[test]
+    fn sharp_s_has_no_upper_case_of_its_own() {
+        assert_eq!(Alphabet::german().normalize_word("Straße"), Some("STRAßE".to_string()));
+        assert_eq!(Alphabet::german().with_case(CaseFolding::Lower).normalize_word("STRAßE"), Some("straße".to_string()));
+        assert_eq!(Alphabet::english().normalize_word("Straße"), None);
+    }
+
+    #[test]
+    fn case_follows_the_alphabet() {
+        assert_eq!(Alphabet::english().normalize_word("Cat"), Some("CAT".to_string()));
+        assert_eq!(Alphabet::english().with_case(CaseFolding::Lower).normalize_word("Cat"), Some("cat".to_string()));
+    }
+
+    #[test]
+    fn unsupported_language_has_no_alphabet() {
+        assert_eq!(Alphabet::for_language("CZ").map(|alphabet| alphabet.language), Some("cs".to_string()));
+        assert_eq!(Alphabet::for_language("xx"), None);
+    }
+}
@@ -0,0 +1,186 @@
+use types::{Board, Cell, Direction};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::board::BoardTrait;
+use crate::policy::PlacementPolicy;
+
+// Every tested (word, direction, offset) candidate counts as a step
+const MAX_BACKTRACKING_STEPS: usize = 2_000_000;
+// When generating at random we only try a sample of the dictionary for each cell
+const MAX_CANDIDATE_WORDS: usize = 250;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchOutcome {
+    Filled,
+    // Every candidate was tried. Without rng that covers the whole dictionary, with rng only the sampled words
+    Exhausted { steps: usize },
+    // Gave up before trying every candidate, nothing is known about the board
+    StepLimit { steps: usize },
+}
+
+struct Search<'a, R: Rng + ?Sized> {
+    words: &'a Vec<String>,
+    policy: &'a PlacementPolicy,
+    rng: Option<&'a mut R>,
+    reserved: Vec<Vec<bool>>,
+    reserved_cells: usize,
+    steps: usize,
+    max_steps: usize,
+}
+
+// Marks that the step budget ran out, unwinds the whole search
+struct StepLimitReached;
+
+// Fills the board so that exactly as many cells as solution letters stay empty. Without rng the search is exhaustive
+// and deterministic, so Exhausted is a proof that the board cannot be filled with given words
+pub fn fill_board_backtracking<R: Rng + ?Sized>(board: &mut Board, words: &Vec<String>, policy: &PlacementPolicy, number_rng: Option<&mut R>) -> SearchOutcome {
+    return fill_board_backtracking_with_limit(board, words, policy, number_rng, MAX_BACKTRACKING_STEPS);
+}
+
+fn fill_board_backtracking_with_limit<R: Rng + ?Sized>(board: &mut Board, words: &Vec<String>, policy: &PlacementPolicy, number_rng: Option<&mut R>, max_steps: usize) -> SearchOutcome {
+    let mut search = Search {
+        words,
+        policy,
+        rng: number_rng,
+        reserved: vec![vec![false; board.cols]; board.rows],
+        reserved_cells: 0,
+        steps: 0,
+        max_steps,
+    };
+
+    return match search.solve(board) {
+        Ok(true) => SearchOutcome::Filled,
+        Ok(false) => SearchOutcome::Exhausted { steps: search.steps },
+        Err(StepLimitReached) => SearchOutcome::StepLimit { steps: search.steps }
+    };
+}
+
+impl<'a, R: Rng + ?Sized> Search<'a, R> {
+    fn solve(&mut self, board: &mut Board) -> Result<bool, StepLimitReached> {
+        if board.is_filled() {
+            return Ok(true);
+        }
+
+        let (row, col) = match self.first_undecided_cell(board) {
+            Some(cell) => cell,
+            None => return Ok(false)
+        };
+
+        // Either some word covers the cell...
+        for word in self.candidate_words() {
+            if board.words.contains(&word) {
+                continue;
+            }
+
+            for direction in self.candidate_directions() {
+                for offset in 0..word.chars().count() {
+                    self.steps += 1;
+                    if self.steps > self.max_steps {
+                        return Err(StepLimitReached);
+                    }
+
+                    let start_row = row as isize - direction.row_step() * offset as isize;
+                    let start_col = col as isize - direction.col_step() * offset as isize;
+
+                    if start_row < 0 || start_col < 0 {
+                        continue;
+                    }
+
+                    let (start_row, start_col) = (start_row as usize, start_col as usize);
+
+                    if board.word_fits_direction(start_row, start_col, &word, &direction).is_none() {
+                        continue;
+                    }
+
+                    if self.covers_reserved_cell(start_row, start_col, &word, &direction) {
+                        continue;
+                    }
+
+                    let (_placement, filled_cells) = board.place_word_in_direction(start_row, start_col, &word, &direction);
+
+                    if self.solve(board)? {
+                        return Ok(true);
+                    }
+
+                    board.remove_word_from_board(&word, &filled_cells);
+                }
+            }
+        }
+
+        // ...or it is left empty for the solution
+        if self.reserved_cells < board.solution.chars().count() {
+            self.reserved[row][col] = true;
+            self.reserved_cells += 1;
+
+            if self.solve(board)? {
+                return Ok(true);
+            }
+
+            self.reserved[row][col] = false;
+            self.reserved_cells -= 1;
+        }
+
+        return Ok(false);
+    }
+
+    fn first_undecided_cell(&self, board: &Board) -> Option<(usize, usize)> {
+        for r in 0..board.rows {
+            for c in 0..board.cols {
+                if board.grid[r][c] == Cell::Empty && !self.reserved[r][c] {
+                    return Some((r, c));
+                }
+            }
+        }
+
+        return None;
+    }
+
+    fn candidate_words(&mut self) -> Vec<String> {
+        match self.rng.as_mut() {
+            Some(rng) => self.words.choose_multiple(rng, MAX_CANDIDATE_WORDS).cloned().collect(),
+            None => self.words.clone()
+        }
+    }
+
+    fn candidate_directions(&mut self) -> Vec<Direction> {
+        match self.rng.as_mut() {
+            Some(rng) => self.policy.weighted_directions(rng),
+            None => self.policy.allowed_directions()
+        }
+    }
+
+    fn covers_reserved_cell(&self, row: usize, col: usize, word: &str, direction: &Direction) -> bool {
+        for curr_depth in 0..word.chars().count() {
+            let row_depth = (row as isize + (direction.row_step() * curr_depth as isize)) as usize;
+            let col_depth = (col as isize + (direction.col_step() * curr_depth as isize)) as usize;
+
+            if self.reserved[row_depth][col_depth] {
+                return true;
+            }
+        }
+
+        return false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::Alphabet;
+    use crate::board::BoardTrait;
+    use crate::SeededRng;
+    use super::*;
+
+    fn east_only() -> PlacementPolicy {
+        PlacementPolicy { directions: vec![(Direction::East, 1)], ..PlacementPolicy::default() }
+    }
+
+    #[test]
+    fn step_limit_is_not_reported_as_exhausted() {
+        let mut board = Board::new(3, 3, String::new(), &Alphabet::english());
+        let words = vec!["CAT".to_string(), "DOG".to_string(), "EMU".to_string()];
+
+        let outcome = fill_board_backtracking_with_limit(&mut board, &words, &east_only(), None::<&mut SeededRng>, 2);
+
+        assert_eq!(outcome, SearchOutcome::StepLimit { steps: 3 });
+    }
+}
@@ -1,37 +1,45 @@
-use types::{Board, Direction, Placement};
+use types::{Board, Cell, Direction, Placement};
 
 use std::collections::HashSet;
-use spacetimedb::rand::prelude::SliceRandom;
-use spacetimedb::{rand::Rng, StdbRng};
-
-const DIRECTIONS: [Direction; 8] = [
-    Direction { y_dir: -1, x_dir: -1, dbg_name: "NW" },   // NW
-    Direction { y_dir: -1, x_dir: 0, dbg_name: "N" },   // N
-    Direction { y_dir: -1, x_dir: 1, dbg_name: "NE" },   // NE
-    Direction { y_dir: 0, x_dir: -1, dbg_name: "W" },   // W
-    Direction { y_dir: 0, x_dir: 1, dbg_name: "E" },   // E
-    Direction { y_dir: 1, x_dir: -1, dbg_name: "SW" },   // SW
-    Direction { y_dir: 1, x_dir: 0, dbg_name: "S" },   // S
-    Direction { y_dir: 1, x_dir: 1, dbg_name: "SE" },   // SE
-];
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::alphabet::Alphabet;
+use crate::options::SolutionOrder;
+use crate::policy::{OverlapPreference, PlacementPolicy};
 
 pub trait BoardTrait {
-    fn new(rows: usize, cols: usize, message: String) -> Board;
+    fn new(rows: usize, cols: usize, message: String, alphabet: &Alphabet) -> Board;
+    fn with_mask(rows: usize, cols: usize, message: String, alphabet: &Alphabet, mask: Vec<Vec<bool>>) -> Board;
     fn is_filled(&self) -> bool;
     fn get_empty_cells(&self) -> usize;
-    fn place_word_on_board(&mut self, word: String, rng: &StdbRng) -> Option<Placement>;
-    fn get_random_cell(&self, rng: &StdbRng) -> (usize, usize);
-    fn word_fits_board_direction(&self, row: usize, col: usize, word: String, rng: &StdbRng) -> Option<Direction>;
+    fn place_word_on_board<R: Rng + ?Sized>(&mut self, word: String, policy: &PlacementPolicy, rng: &mut R) -> Option<Placement>;
+    fn get_random_cell<R: Rng + ?Sized>(&self, rng: &mut R) -> (usize, usize);
+    fn word_fits_board_direction<R: Rng + ?Sized>(&self, row: usize, col: usize, word: String, policy: &PlacementPolicy, rng: &mut R) -> Option<Direction>;
+    fn place_word_anywhere<R: Rng + ?Sized>(&mut self, word: String, policy: &PlacementPolicy, overlap: OverlapPreference, rng: &mut R) -> Option<Placement>;
+    fn word_fits_direction(&self, row: usize, col: usize, word: &str, direction: &Direction) -> Option<usize>;
+    fn place_word_in_direction(&mut self, row: usize, col: usize, word: &str, direction: &Direction) -> (Placement, Vec<(usize, usize)>);
+    fn remove_word_from_board(&mut self, word: &str, filled_cells: &[(usize, usize)]);
+    fn fill_solution(&mut self, order: SolutionOrder);
 }
 
 impl BoardTrait for Board {
-    fn new(rows: usize, cols: usize, message: String) -> Self {
-        let solution = message.to_uppercase().chars().filter(|c| c.is_alphabetic()).collect();
+    fn new(rows: usize, cols: usize, message: String, alphabet: &Alphabet) -> Self {
+        return Board::with_mask(rows, cols, message, alphabet, vec![vec![true; cols]; rows]);
+    }
+
+    fn with_mask(rows: usize, cols: usize, message: String, alphabet: &Alphabet, mask: Vec<Vec<bool>>) -> Self {
+        let solution = alphabet.normalize_message(&message);
+        let grid: Vec<Vec<Cell>> = mask.iter()
+            .map(|r| r.iter().map(|open| if *open { Cell::Empty } else { Cell::Blocked }).collect())
+            .collect();
+
         Board {
             cols, rows,
             message, solution,
-            grid: vec![vec!['?'; cols]; rows],
-            words: HashSet::new()
+            grid,
+            words: HashSet::new(),
+            placements: Vec::new(),
+            solution_cells: Vec::new()
         }
     }
 
@@ -40,20 +48,20 @@ impl BoardTrait for Board {
 
         for r in 0..self.rows {
             for c in 0..self.cols {
-                if self.grid[r][c] == '?' {
+                if self.grid[r][c] == Cell::Empty {
                     items += 1;
                 }
             }
         }
 
-        return items == self.solution.len();
+        return items == self.solution.chars().count();
     }
 
     fn get_empty_cells(&self) -> usize {
         let mut items: usize = 0;
         for r in 0..self.rows {
             for c in 0..self.cols {
-                if self.grid[r][c] == '?' {
+                if self.grid[r][c] == Cell::Empty {
                     items += 1;
                 }
             }
@@ -62,38 +70,79 @@ impl BoardTrait for Board {
         return items;
     }
 
-    fn place_word_on_board(&mut self, word: String, rng: &StdbRng) -> Option<Placement> {
+    fn place_word_on_board<R: Rng + ?Sized>(&mut self, word: String, policy: &PlacementPolicy, rng: &mut R) -> Option<Placement> {
         let (row, col) = self.get_random_cell(rng);
-        // FIXME: We should ensure that word is not part of solution - this may be problem if solution 'stable' will be placed in allowed direction fully or partially and words like 'table, able' might fit there
-        let o_direction = self.word_fits_board_direction(row, col, word.clone(), rng);
+        // Words may still spell parts of the solution by accident - verifier catches those when unique reading is requested
+        let o_direction = self.word_fits_board_direction(row, col, word.clone(), policy, rng);
 
         if o_direction.is_some() {
             let direction = o_direction.unwrap();
+            let (placement, _filled_cells) = self.place_word_in_direction(row, col, &word, &direction);
 
-            for (curr_depth, curr_char) in word.chars().enumerate() {
-                let irow_depth: isize = row as isize + (direction.y_dir * curr_depth as isize);
-                let icol_depth: isize = col as isize + (direction.x_dir * curr_depth as isize);
+            return Some(placement);
+        }
 
-                self.grid[irow_depth as usize][icol_depth as usize] = curr_char;
-            }
+        return None;
+    }
 
-            self.words.insert(word.clone());
+    // Unlike place_word_on_board every cell is tried, so the word may also start on a filled cell as long as
+    // it fills at least one empty cell. Overlap preference decides between candidates sharing more or fewer letters
+    fn place_word_anywhere<R: Rng + ?Sized>(&mut self, word: String, policy: &PlacementPolicy, overlap: OverlapPreference, rng: &mut R) -> Option<Placement> {
+        if self.words.contains(&word) {
+            return None;
+        }
+
+        let word_length = word.chars().count();
+        let mut best_overlaps: usize = 0;
+        let mut best_candidates: Vec<(usize, usize, Direction, u32)> = Vec::new();
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                for (direction, weight) in policy.directions.iter().filter(|(_, weight)| *weight > 0) {
+                    let non_filled_cells = match self.word_fits_direction(row, col, &word, direction) {
+                        Some(non_filled_cells) if non_filled_cells > 0 => non_filled_cells,
+                        _ => continue
+                    };
+
+                    let overlaps = word_length - non_filled_cells;
+
+                    let is_better = match overlap {
+                        OverlapPreference::Allow => false,
+                        OverlapPreference::Prefer => overlaps > best_overlaps,
+                        OverlapPreference::Avoid => overlaps < best_overlaps,
+                    };
+
+                    if is_better || best_candidates.is_empty() {
+                        best_overlaps = overlaps;
+                        best_candidates.clear();
+                    }
 
-            return Some( Placement {
-                word, row, col, direction: direction.dbg_name, step: self.words.len()
-            });
+                    if overlaps == best_overlaps || overlap == OverlapPreference::Allow {
+                        best_candidates.push((row, col, *direction, *weight));
+                    }
+                }
+            }
         }
 
-        return None;
+        // among equally good placements the policy's direction weights decide
+        let (row, col, direction, _) = match best_candidates.choose_weighted(rng, |(_, _, _, weight)| *weight) {
+            Ok(candidate) => *candidate,
+            Err(_) => return None
+        };
+
+        let (placement, _filled_cells) = self.place_word_in_direction(row, col, &word, &direction);
+
+        return Some(placement);
     }
 
-    fn get_random_cell(&self, mut rng: &StdbRng) -> (usize, usize) {
+    fn get_random_cell<R: Rng + ?Sized>(&self, rng: &mut R) -> (usize, usize) {
         // make it start at random
         // TODO : Update RND
         let mut row = rng.gen_range(0..self.rows);
         let mut col = rng.gen_range(0..self.cols);
 
-        while self.grid[row][col] != '?' {
+        // blocked cells are skipped the same way as filled ones
+        while self.grid[row][col] != Cell::Empty {
             col += 1;
             if col == self.cols {
                 col = 0;
@@ -105,7 +154,7 @@ impl BoardTrait for Board {
         (row, col)
     }
 
-    fn word_fits_board_direction(&self, row: usize, col: usize, word: String, mut rng: &StdbRng) -> Option<Direction> {
+    fn word_fits_board_direction<R: Rng + ?Sized>(&self, row: usize, col: usize, word: String, policy: &PlacementPolicy, rng: &mut R) -> Option<Direction> {
         if self.words.contains(&word) {
             return None
         }
@@ -114,47 +163,192 @@ impl BoardTrait for Board {
         //     return None;
         // }
 
-        let mut random_directions = DIRECTIONS.clone();
-        // TODO : Update RND
-        random_directions.shuffle(&mut rng);
+        let random_directions = policy.weighted_directions(rng);
+        let word_length = word.chars().count();
+        let mut best_direction: Option<(Direction, usize)> = None;
 
         for direction in random_directions {
-            let mut non_filled_cells: usize = 0;
-            for (curr_depth, curr_char) in word.chars().enumerate() {
-                let irow_depth: isize = row as isize + (direction.y_dir * curr_depth as isize);
-                let icol_depth: isize = col as isize + (direction.x_dir * curr_depth as isize);
+            if let Some(non_filled_cells) = self.word_fits_direction(row, col, &word, &direction) {
+                let overlaps = word_length - non_filled_cells;
+
+                let is_better = match (&best_direction, policy.overlap) {
+                    (None, _) => true,
+                    (Some(_), OverlapPreference::Allow) => false,
+                    (Some((_, best_overlaps)), OverlapPreference::Prefer) => overlaps > *best_overlaps,
+                    (Some((_, best_overlaps)), OverlapPreference::Avoid) => overlaps < *best_overlaps,
+                };
+
+                if is_better {
+                    best_direction = Some((direction, overlaps));
+                }
 
-                if irow_depth < 0 || icol_depth < 0 {
+                // without preference the first fitting direction in weighted order wins
+                if policy.overlap == OverlapPreference::Allow {
                     break;
                 }
+            }
+        }
 
-                let row_depth: usize = irow_depth as usize;
-                let col_depth: usize = icol_depth as usize;
+        return best_direction.map(|(direction, _)| direction);
+    }
 
-                if row_depth >= self.rows || col_depth >= self.cols {
-                    break;
-                }
+    // Returns number of empty cells the word would fill if it fits the board in given direction
+    fn word_fits_direction(&self, row: usize, col: usize, word: &str, direction: &Direction) -> Option<usize> {
+        let mut non_filled_cells: usize = 0;
 
-                let cell_char = self.grid[row_depth][col_depth];
+        for (curr_depth, curr_char) in word.chars().enumerate() {
+            let irow_depth: isize = row as isize + (direction.row_step() * curr_depth as isize);
+            let icol_depth: isize = col as isize + (direction.col_step() * curr_depth as isize);
 
-                if cell_char != '?' && curr_char != cell_char {
-                    break;
-                }
+            if irow_depth < 0 || icol_depth < 0 {
+                return None;
+            }
 
-                if cell_char == '?' {
-                    non_filled_cells += 1;
-                }
+            let row_depth: usize = irow_depth as usize;
+            let col_depth: usize = icol_depth as usize;
 
-                if curr_depth == word.len() - 1 {
-                    if (self.solution.len() + non_filled_cells) > self.get_empty_cells() {
-                        break;
-                    }
+            if row_depth >= self.rows || col_depth >= self.cols {
+                return None;
+            }
 
-                    return Some(direction);
+            match self.grid[row_depth][col_depth] {
+                Cell::Empty => non_filled_cells += 1,
+                Cell::Letter(letter) if letter == curr_char => (),
+                _ => return None
+            }
+        }
+
+        if (self.solution.chars().count() + non_filled_cells) > self.get_empty_cells() {
+            return None;
+        }
+
+        return Some(non_filled_cells);
+    }
+
+    // Records the placement on the board and returns it with the cells that were empty before
+    fn place_word_in_direction(&mut self, row: usize, col: usize, word: &str, direction: &Direction) -> (Placement, Vec<(usize, usize)>) {
+        let mut filled_cells: Vec<(usize, usize)> = Vec::new();
+
+        for (curr_depth, curr_char) in word.chars().enumerate() {
+            let row_depth = (row as isize + (direction.row_step() * curr_depth as isize)) as usize;
+            let col_depth = (col as isize + (direction.col_step() * curr_depth as isize)) as usize;
+
+            if self.grid[row_depth][col_depth] == Cell::Empty {
+                filled_cells.push((row_depth, col_depth));
+            }
+
+            self.grid[row_depth][col_depth] = Cell::Letter(curr_char);
+        }
+
+        let placement = Placement {
+            word: word.to_string(), row, col, direction: *direction, step: self.placements.len()
+        };
+
+        self.words.insert(word.to_string());
+        self.placements.push(placement.clone());
+
+        return (placement, filled_cells);
+    }
+
+    // Reverts place_word_in_direction - only cells the word filled are emptied, crossing words stay intact
+    fn remove_word_from_board(&mut self, word: &str, filled_cells: &[(usize, usize)]) {
+        for (row, col) in filled_cells {
+            self.grid[*row][*col] = Cell::Empty;
+        }
+
+        self.words.remove(word);
+        self.placements.retain(|placement| placement.word != word);
+    }
+
+    // Writes the solution into the cells that are still empty once all words are placed
+    fn fill_solution(&mut self, order: SolutionOrder) {
+        let mut solution = self.solution.chars();
+
+        for (row, col) in reading_order(self.rows, self.cols, order) {
+            if self.grid[row][col] != Cell::Empty {
+                continue;
+            }
+
+            if let Some(letter) = solution.next() {
+                self.grid[row][col] = Cell::Solution(letter);
+                self.solution_cells.push((row, col));
+            }
+        }
+    }
+}
+
+pub(crate) fn reading_order(rows: usize, cols: usize, order: SolutionOrder) -> Vec<(usize, usize)> {
+    match order {
+        SolutionOrder::RowMajor => (0..rows).flat_map(|r| (0..cols).map(move |c| (r, c))).collect(),
+        SolutionOrder::ColumnMajor => (0..cols).flat_map(|c| (0..rows).map(move |r| (r, c))).collect(),
+        SolutionOrder::Spiral => {
+            let mut cells: Vec<(usize, usize)> = Vec::new();
+            if rows == 0 || cols == 0 {
+                return cells;
+            }
+
+            let (mut top, mut bottom, mut left, mut right) = (0, rows - 1, 0, cols - 1);
+
+            while top <= bottom && left <= right {
+                for c in left..=right { cells.push((top, c)); }
+                for r in top + 1..=bottom { cells.push((r, right)); }
+
+                if top < bottom && left < right {
+                    for c in (left..right).rev() { cells.push((bottom, c)); }
+                    for r in (top + 1..bottom).rev() { cells.push((r, left)); }
                 }
+
+                if bottom == 0 || right == 0 {
+                    break;
+                }
+
+                top += 1; left += 1;
+                bottom -= 1; right -= 1;
             }
+
+            cells
         }
+    }
+}
 
-        return None;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // CAT across the middle row leaves the top and bottom rows for the solution
+    fn board_with_middle_row_taken(message: &str) -> Board {
+        let mut board = Board::new(3, 3, message.to_string(), &Alphabet::english());
+        board.place_word_in_direction(1, 0, "CAT", &Direction::East);
+
+        return board;
+    }
+
+    fn to_rows(board: &Board) -> Vec<String> {
+        return board.grid.iter().map(|row| row.iter().map(|cell| cell.to_char()).collect()).collect();
+    }
+
+    #[test]
+    fn column_major_fills_the_solution_column_by_column() {
+        let mut board = board_with_middle_row_taken("ABCDEF");
+        board.fill_solution(SolutionOrder::ColumnMajor);
+
+        assert_eq!(to_rows(&board), vec!["ACE", "CAT", "BDF"]);
+        assert_eq!(board.solution_cells, vec![(0, 0), (2, 0), (0, 1), (2, 1), (0, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn spiral_fills_the_solution_clockwise_from_the_top_left() {
+        let mut board = board_with_middle_row_taken("ABCDEF");
+        board.fill_solution(SolutionOrder::Spiral);
+
+        assert_eq!(to_rows(&board), vec!["ABC", "CAT", "FED"]);
+        assert_eq!(board.solution_cells, vec![(0, 0), (0, 1), (0, 2), (2, 2), (2, 1), (2, 0)]);
+    }
+
+    #[test]
+    fn spiral_reading_order_ends_in_the_middle() {
+        assert_eq!(reading_order(3, 3, SolutionOrder::Spiral), vec![
+            (0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 1), (2, 0), (1, 0), (1, 1)
+        ]);
     }
 }
@@ -0,0 +1,114 @@
+use std::path::Path;
+use crate::error::GenerationError;
+
+// Dictionary files are named <language>.dr for general words and <language>.<category>.dr for themed ones
+const DICTIONARY_EXTENSION: &str = "dr";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogueEntry {
+    pub word: String,
+    pub language: String,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Catalogue {
+    entries: Vec<CatalogueEntry>,
+}
+
+impl Catalogue {
+    pub fn from_entries(entries: Vec<CatalogueEntry>) -> Self {
+        return Catalogue { entries };
+    }
+
+    // Loads every dictionary file in the directory, or only files of one language when it is given
+    pub fn from_directory(directory: &str, language: Option<&str>) -> Result<Self, GenerationError> {
+        let dictionary_files = std::fs::read_dir(directory)
+            .map_err(|e| GenerationError::DictionaryUnavailable(format!("{}: {}", directory, e)))?;
+
+        let mut catalogue = Catalogue::default();
+
+        for dictionary_file in dictionary_files {
+            let path = match dictionary_file {
+                Ok(entry) => entry.path(),
+                Err(_) => continue
+            };
+
+            let (file_language, category) = match parse_file_name(&path) {
+                Some(parsed) => parsed,
+                None => continue
+            };
+
+            if language.is_some_and(|l| l != file_language) {
+                continue;
+            }
+
+            catalogue.load_file(&path, &file_language, category)?;
+        }
+
+        return Ok(catalogue);
+    }
+
+    pub fn load_file(&mut self, path: &Path, language: &str, category: Option<String>) -> Result<(), GenerationError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| GenerationError::DictionaryUnavailable(format!("{}: {}", path.display(), e)))?;
+
+        for word in content.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            self.entries.push(CatalogueEntry {
+                word: word.to_string(), language: language.to_string(), category: category.clone()
+            });
+        }
+
+        return Ok(());
+    }
+
+    pub fn languages(&self) -> Vec<String> {
+        let mut languages: Vec<String> = self.entries.iter().map(|e| e.language.clone()).collect();
+        languages.sort();
+        languages.dedup();
+
+        return languages;
+    }
+
+    pub fn categories(&self, language: &str) -> Vec<String> {
+        let mut categories: Vec<String> = self.entries.iter()
+            .filter(|e| e.language == language)
+            .filter_map(|e| e.category.clone())
+            .collect();
+        categories.sort();
+        categories.dedup();
+
+        return categories;
+    }
+
+    // Without category all words of the language are used, including the themed ones
+    pub fn select(&self, language: &str, category: Option<&str>) -> Result<Vec<String>, GenerationError> {
+        let words: Vec<String> = self.entries.iter()
+            .filter(|e| e.language == language)
+            .filter(|e| category.is_none() || e.category.as_deref() == category)
+            .map(|e| e.word.clone())
+            .collect();
+
+        if words.is_empty() {
+            return Err(GenerationError::DictionaryUnavailable(match category {
+                Some(category) => format!("no words in category {} for language {}", category, language),
+                None => format!("no words for language {}", language)
+            }));
+        }
+
+        return Ok(words);
+    }
+}
+
+fn parse_file_name(path: &Path) -> Option<(String, Option<String>)> {
+    if path.extension()?.to_str()? != DICTIONARY_EXTENSION {
+        return None;
+    }
+
+    let stem = path.file_stem()?.to_str()?;
+
+    return match stem.split_once('.') {
+        Some((language, category)) => Some((language.to_string(), Some(category.to_string()))),
+        None => Some((stem.to_string(), None))
+    };
+}
@@ -1,59 +1,66 @@
 use std::slice::IterMut;
-use spacetimedb::rand::prelude::SliceRandom;
-use spacetimedb::{rand::Rng, StdbRng};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::alphabet::Alphabet;
 
-const MIN_WORD_LENGTH: usize = 3;
+pub const MIN_WORD_LENGTH: usize = 3;
 
+#[derive(Clone)]
 pub struct Dictionary {
     words: Vec<String>
 }
 
 impl Dictionary {
-    pub fn from_file(file_path: String, max_word_length: usize) -> Self {
-        let items: Vec<String> = std::fs::read_to_string(file_path)
-            .unwrap()  // panic on possible file-reading errors
-            .lines()  // split the string into an iterator of string slices
-            .map(String::from)  // make each slice into a string
-            .filter(|s| s.len() >= MIN_WORD_LENGTH && s.len() <= max_word_length)
-            .map(|s| s.to_uppercase())
-            .collect();  // gather them together into a vector
+    pub fn from_words<R: Rng + ?Sized>(items: Vec<String>, alphabet: &Alphabet, min_word_length: usize, max_word_length: usize, rng: &mut R) -> Self {
+        let filtered_items: Vec<String> = Self::filter_words(&items, alphabet, min_word_length, max_word_length);
 
-        // TODO : Update RND
-        Dictionary {
-            words: items
-                .choose_multiple(&mut spacetimedb::rand::thread_rng(), items.len())
+        return Dictionary {
+            words: filtered_items
+                .choose_multiple(rng, filtered_items.len())
                 .cloned().collect()
-        }
+        };
     }
 
-    pub fn from_words(items: Vec<String>, max_word_length: usize, mut rng: &StdbRng) -> Self {
-        let filtered_items: Vec<String> = items.iter()
-            .map(String::from)
-            .filter(|s| s.len() >= MIN_WORD_LENGTH && s.len() <= max_word_length)
-            .map(|s| s.to_uppercase())
+    // Keeps the words in a stable order so searches over the dictionary are reproducible
+    pub fn from_words_sorted(items: Vec<String>, alphabet: &Alphabet, min_word_length: usize, max_word_length: usize) -> Self {
+        return Dictionary { words: Self::filter_words(&items, alphabet, min_word_length, max_word_length) };
+    }
+
+    // Sorted regardless of the input order, so the same words with the same seed give the same board.
+    // Lengths are counted in chars of the normalized word, so Ž is one letter just like Z
+    fn filter_words(items: &[String], alphabet: &Alphabet, min_word_length: usize, max_word_length: usize) -> Vec<String> {
+        let mut filtered_items: Vec<String> = items.iter()
+            .filter_map(|s| alphabet.normalize_word(s))
+            .filter(|s| s.chars().count() >= min_word_length && s.chars().count() <= max_word_length)
             .collect();  // gather them together into a vector
 
-        return Dictionary {
-            words: filtered_items
-                .choose_multiple(&mut rng, filtered_items.len())
-                .cloned().collect()
-        };
+        filtered_items.sort();
+        filtered_items.dedup();
+
+        return filtered_items;
+    }
+
+    pub fn words(&self) -> &Vec<String> {
+        return &self.words;
     }
 
     pub fn _iterate(&mut self) -> IterMut<'_, String> {
         return self.words.iter_mut()
     }
 
-    pub fn get_random_word(&mut self, mut rng: &StdbRng) -> String {
-        let mut word: Option<&String> = None;
+    pub fn get_random_word<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<String> {
+        if self.words.is_empty() {
+            return None;
+        }
 
-        while word.is_none() {
-            // TODO : Update RND
-            let rnd_idx = rng.gen_range(0..self.words.len());
+        let rnd_idx = rng.gen_range(0..self.words.len());
 
-            word = self.words.get(rnd_idx);
-        }
+        return self.words.get(rnd_idx).cloned();
+    }
 
-        return word.unwrap().to_string();
+    pub fn remove_word(&mut self, word: &String) {
+        if let Some(idx) = self.words.iter().position(|w| w == word) {
+            self.words.swap_remove(idx);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenerationError {
+    MessageTooLong { solution_length: usize, cells: usize },
+    DictionaryUnavailable(String),
+    DictionaryExhausted,
+    AttemptBudgetExceeded { attempts: usize },
+    AmbiguousBoard { rerolls: usize, issues: usize },
+    WordsDidNotFit(Vec<String>),
+    NoGridSize { required_cells: usize, max_side: usize },
+    InvalidMask(String),
+}
+
+impl Display for GenerationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenerationError::MessageTooLong { solution_length, cells } => write!(
+                f, "Message has {} letters but the board only has {} cells", solution_length, cells
+            ),
+            GenerationError::DictionaryUnavailable(reason) => write!(
+                f, "Dictionary could not be loaded: {}", reason
+            ),
+            GenerationError::DictionaryExhausted => write!(
+                f, "Dictionary has no more words that could be placed on the board"
+            ),
+            GenerationError::AttemptBudgetExceeded { attempts } => write!(
+                f, "Board could not be filled after {} attempts", attempts
+            ),
+            GenerationError::AmbiguousBoard { rerolls, issues } => write!(
+                f, "Board still had {} unintended readings after {} rerolls", issues, rerolls
+            ),
+            GenerationError::WordsDidNotFit(words) => write!(
+                f, "Words did not fit the board: {}", words.join(", ")
+            ),
+            GenerationError::NoGridSize { required_cells, max_side } => write!(
+                f, "No grid up to {}x{} has the {} cells the message and words need", max_side, max_side, required_cells
+            ),
+            GenerationError::InvalidMask(reason) => write!(
+                f, "Board mask is not valid: {}", reason
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenerationError {}
@@ -1,48 +1,316 @@
-use std::cmp::max;
+use std::collections::HashSet;
 use types::{Board, Placement};
 use crate::board::BoardTrait;
-use crate::dictionary::Dictionary;
-use spacetimedb::{StdbRng};
-use spacetimedb::rand::prelude::SliceRandom;
+use crate::backtracking::{fill_board_backtracking, SearchOutcome};
+use rand::{Rng, SeedableRng};
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
 
+pub use crate::alphabet::{Alphabet, CaseFolding};
+pub use crate::catalogue::{Catalogue, CatalogueEntry};
+pub use crate::dictionary::Dictionary;
+pub use crate::error::GenerationError;
+pub use crate::options::{GenerationStrategy, GeneratorOptions, SolutionOrder};
+pub use crate::policy::{Difficulty, OverlapPreference, PlacementPolicy};
+pub use crate::shape::BoardShape;
+pub use crate::sizing::{auto_grid_size, AutoSize, MAX_GRID_SIDE, MIN_GRID_SIDE};
+pub use crate::solver::{leftover_letters, Occurrence, Solver};
+pub use crate::verifier::{verify_board, VerificationIssue};
+
+mod alphabet;
+mod backtracking;
+mod catalogue;
 mod board;
 mod dictionary;
+mod error;
+mod options;
+mod policy;
+mod shape;
+mod sizing;
+mod solver;
+mod verifier;
 
-pub fn generate_board(rows: usize, cols: usize, message: String, number_rng: &StdbRng) -> Board {
-    let mut board: Board = Board::new(rows, cols, message.clone());
-    let mut dictionary: Dictionary = Dictionary::from_file("./res/en.dr".to_string(), max(rows, cols));
-    let mut placements: Vec<Placement> = Vec::new();
+// Number of consecutive unsuccessful placements after which we give up on the board
+const MAX_FAILED_ATTEMPTS: usize = 5000;
+// Number of boards generated in search of one with a single intended reading
+const MAX_REROLLS: usize = 20;
+// Number of times an ambiguous board is patched up before it is thrown away
+const MAX_REPAIRS: usize = 5;
+// Shorter dictionary words show up by chance on nearly every board, of those only the placed words are checked
+const MIN_INCIDENTAL_WORD_LENGTH: usize = 5;
 
-    while !board.is_filled() {
-        let random_word = dictionary.get_random_word(number_rng);
-        let valid_placement = board.place_word_on_board(random_word, number_rng);
+pub const DICTIONARY_DIRECTORY: &str = "./res";
 
-        if valid_placement.is_some() {
-            placements.push(valid_placement.unwrap());
+// ChaCha produces the same stream on every platform and rand version, unlike StdRng
+pub type SeededRng = ChaCha8Rng;
+
+pub fn rng_from_seed(seed: u64) -> SeededRng {
+    return ChaCha8Rng::seed_from_u64(seed);
+}
+
+pub fn random_seed() -> u64 {
+    return rand::thread_rng().r#gen();
+}
+
+pub fn generate_board<R: Rng + ?Sized>(rows: usize, cols: usize, message: String, options: &GeneratorOptions, number_rng: &mut R) -> Result<Board, GenerationError> {
+    let catalogue: Catalogue = Catalogue::from_directory(DICTIONARY_DIRECTORY, Some(&options.alphabet.language))?;
+
+    return generate_board_from_catalogue(rows, cols, message, &catalogue, options, number_rng);
+}
+
+// Same as generate_board with a catalogue loaded beforehand, so it is not read from disk for every board
+pub fn generate_board_from_catalogue<R: Rng + ?Sized>(rows: usize, cols: usize, message: String, catalogue: &Catalogue, options: &GeneratorOptions, number_rng: &mut R) -> Result<Board, GenerationError> {
+    let words = catalogue.select(&options.alphabet.language, options.category.as_deref())?;
+
+    let (min_word_length, max_word_length) = options.policy.word_length_range(rows, cols);
+    let dictionary: Dictionary = Dictionary::from_words_sorted(words, &options.alphabet, min_word_length, max_word_length);
+
+    return generate(rows, cols, message, &Vec::new(), dictionary, options, number_rng);
+}
+
+pub fn generate_board_with_dictionary<R: Rng + ?Sized>(rows: usize, cols: usize, message: String, words: &Vec<String>, options: &GeneratorOptions, number_rng: &mut R) -> Result<Board, GenerationError> {
+    let (min_word_length, max_word_length) = options.policy.word_length_range(rows, cols);
+    let dictionary: Dictionary = Dictionary::from_words(words.clone(), &options.alphabet, min_word_length, max_word_length, number_rng);
+
+    return generate(rows, cols, message, &Vec::new(), dictionary, options, number_rng);
+}
+
+// Every required word ends up on the board or the error lists those that did not fit. Remaining cells are
+// filled from filler words, without them the required words alone have to leave exactly the solution cells empty
+pub fn generate_board_with_required_words<R: Rng + ?Sized>(rows: usize, cols: usize, message: String, required_words: &Vec<String>, filler_words: Option<&Vec<String>>, options: &GeneratorOptions, number_rng: &mut R) -> Result<Board, GenerationError> {
+    let (min_word_length, max_word_length) = options.policy.word_length_range(rows, cols);
+
+    let mut required: Vec<String> = Vec::new();
+    let mut unusable: Vec<String> = Vec::new();
+
+    for word in required_words {
+        match options.alphabet.normalize_word(word) {
+            Some(normalized) if normalized.chars().count() >= min_word_length && normalized.chars().count() <= max_word_length => {
+                if !required.contains(&normalized) {
+                    required.push(normalized);
+                }
+            },
+            _ => unusable.push(word.clone())
+        }
+    }
+
+    if !unusable.is_empty() {
+        return Err(GenerationError::WordsDidNotFit(unusable));
+    }
+
+    // longest words are the hardest to fit so they go first
+    required.sort_by(|a, b| b.chars().count().cmp(&a.chars().count()).then(a.cmp(b)));
+
+    let mut dictionary: Dictionary = match filler_words {
+        Some(words) => Dictionary::from_words(words.clone(), &options.alphabet, min_word_length, max_word_length, number_rng),
+        None => Dictionary::from_words_sorted(Vec::new(), &options.alphabet, min_word_length, max_word_length)
+    };
+
+    // required words are already on the board, drawing them as filler would only count as failed attempts
+    for word in &required {
+        dictionary.remove_word(word);
+    }
+
+    return generate(rows, cols, message, &required, dictionary, options, number_rng);
+}
+
+fn generate<R: Rng + ?Sized>(rows: usize, cols: usize, message: String, required: &Vec<String>, dictionary: Dictionary, options: &GeneratorOptions, number_rng: &mut R) -> Result<Board, GenerationError> {
+    let mut rerolls: usize = 0;
+    let min_verified_length = options.policy.min_word_length.max(MIN_INCIDENTAL_WORD_LENGTH);
+    let long_words: Vec<String> = dictionary.words().iter()
+        .filter(|word| word.chars().count() >= min_verified_length)
+        .cloned()
+        .collect();
+
+    loop {
+        let mut board: Board = Board::with_mask(rows, cols, message.clone(), &options.alphabet, options.shape.mask(rows, cols)?);
+        validate_board(&board)?;
+
+        let attempt = place_required_words(&mut board, required, &options.policy, number_rng)
+            .and_then(|_| {
+                // filling consumes the dictionary so every attempt starts with a fresh copy
+                let mut attempt_dictionary = dictionary.clone();
+                fill_board(&mut board, &mut attempt_dictionary, options, number_rng)
+            });
+
+        rerolls += 1;
+
+        match attempt {
+            Ok(()) => (),
+            Err(error) if rerolls >= MAX_REROLLS => return Err(error),
+            Err(_) => continue
+        };
+
+        board.fill_solution(options.solution_order);
+
+        if !options.unique_reading {
+            return Ok(board);
+        }
+
+        let mut issues = verify_board(&board, &verification_words(&long_words, &board), options.policy.min_word_length);
+        // words taken off by a repair are not drawn again for this board
+        let mut repair_dictionary = dictionary.clone();
+
+        for _ in 0..MAX_REPAIRS {
+            if issues.is_empty() {
+                break;
+            }
+
+            match repair_board(&board, &issues, required, &mut repair_dictionary, options, number_rng) {
+                Some(repaired) => board = repaired,
+                None => break
+            };
+
+            issues = verify_board(&board, &verification_words(&long_words, &board), options.policy.min_word_length);
+        }
+
+        if issues.is_empty() {
+            return Ok(board);
+        }
+
+        if rerolls >= MAX_REROLLS {
+            return Err(GenerationError::AmbiguousBoard { rerolls, issues: issues.len() });
         }
     }
+}
+
+// A placed word of any length found a second time would leave the player with two answers
+fn verification_words(long_words: &[String], board: &Board) -> Vec<String> {
+    let mut words: Vec<String> = long_words.to_vec();
+    words.extend(board.placements.iter().map(|placement| placement.word.clone()));
 
-    return board;
+    return words;
 }
 
-pub fn generate_board_with_dictionary(rows: usize, cols: usize, message: String, words: &Vec<String>, number_rng: &StdbRng) -> (Board, Vec<Placement>) {
-    let mut board: Board = Board::new(rows, cols, message.clone());
-    let mut dictionary: Dictionary = Dictionary::from_words(words.clone(), max(rows, cols), number_rng);
-    let mut placements: Vec<Placement> = Vec::new();
+// Takes the filler words crossing the reported readings off the board and fills their cells with other words, so
+// one unlucky word does not cost the whole board. Required words stay where they are. None when there is nothing
+// to take off or the cells cannot be filled again
+fn repair_board<R: Rng + ?Sized>(board: &Board, issues: &[VerificationIssue], required: &[String], dictionary: &mut Dictionary, options: &GeneratorOptions, number_rng: &mut R) -> Option<Board> {
+    let offending_cells: HashSet<(usize, usize)> = issues.iter().flat_map(|issue| issue.cells()).collect();
+    let (kept, dropped): (Vec<&Placement>, Vec<&Placement>) = board.placements.iter()
+        .partition(|placement| required.contains(&placement.word) || placement.cells().iter().all(|cell| !offending_cells.contains(cell)));
+
+    if dropped.is_empty() {
+        return None;
+    }
+
+    for placement in dropped {
+        dictionary.remove_word(&placement.word);
+    }
+
+    let mut repaired: Board = Board::with_mask(board.rows, board.cols, board.message.clone(), &options.alphabet, options.shape.mask(board.rows, board.cols).ok()?);
+    let mut fill_dictionary = dictionary.clone();
+
+    for placement in kept {
+        repaired.place_word_in_direction(placement.row, placement.col, &placement.word, &placement.direction);
+        fill_dictionary.remove_word(&placement.word);
+    }
+
+    fill_board(&mut repaired, &mut fill_dictionary, options, number_rng).ok()?;
+    repaired.fill_solution(options.solution_order);
+
+    return Some(repaired);
+}
+
+fn place_required_words<R: Rng + ?Sized>(board: &mut Board, required: &Vec<String>, policy: &PlacementPolicy, number_rng: &mut R) -> Result<(), GenerationError> {
+    let mut did_not_fit: Vec<String> = Vec::new();
+
+    for word in required {
+        if board.place_word_anywhere(word.clone(), policy, policy.overlap, number_rng).is_none() {
+            did_not_fit.push(word.clone());
+        }
+    }
+
+    if !did_not_fit.is_empty() {
+        return Err(GenerationError::WordsDidNotFit(did_not_fit));
+    }
+
+    return Ok(());
+}
+
+fn validate_board(board: &Board) -> Result<(), GenerationError> {
+    let cells = board.open_cells();
+
+    let solution_length = board.solution.chars().count();
+
+    if solution_length > cells {
+        return Err(GenerationError::MessageTooLong { solution_length, cells });
+    }
+
+    return Ok(());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fillability {
+    Fillable,
+    // every placement of the words was tried
+    Unfillable,
+    // the search ran out of steps before it could decide
+    Inconclusive { steps: usize },
+}
+
+// Exhaustively searches all placements of the words, so the answer does not depend on any randomness
+pub fn is_board_fillable(rows: usize, cols: usize, message: String, words: &[String], policy: &PlacementPolicy, alphabet: &Alphabet) -> Result<Fillability, GenerationError> {
+    let mut board: Board = Board::new(rows, cols, message, alphabet);
+    validate_board(&board)?;
+
+    let (min_word_length, max_word_length) = policy.word_length_range(rows, cols);
+    let dictionary: Dictionary = Dictionary::from_words_sorted(words.to_vec(), alphabet, min_word_length, max_word_length);
+
+    return Ok(match fill_board_backtracking(&mut board, dictionary.words(), policy, None::<&mut SeededRng>) {
+        SearchOutcome::Filled => Fillability::Fillable,
+        SearchOutcome::Exhausted { .. } => Fillability::Unfillable,
+        SearchOutcome::StepLimit { steps } => Fillability::Inconclusive { steps }
+    });
+}
+
+fn fill_board<R: Rng + ?Sized>(board: &mut Board, dictionary: &mut Dictionary, options: &GeneratorOptions, number_rng: &mut R) -> Result<(), GenerationError> {
+    match options.strategy {
+        GenerationStrategy::Greedy => fill_board_greedy(board, dictionary, &options.policy, false, number_rng),
+        GenerationStrategy::Dense => fill_board_greedy(board, dictionary, &options.policy, true, number_rng),
+        GenerationStrategy::Backtracking => {
+            // only a sample of the dictionary is tried for every cell, so running out of candidates says
+            // nothing about the dictionary itself
+            match fill_board_backtracking(board, dictionary.words(), &options.policy, Some(number_rng)) {
+                SearchOutcome::Filled => Ok(()),
+                SearchOutcome::Exhausted { steps } | SearchOutcome::StepLimit { steps } => Err(GenerationError::AttemptBudgetExceeded { attempts: steps })
+            }
+        }
+    }
+}
+
+fn fill_board_greedy<R: Rng + ?Sized>(board: &mut Board, dictionary: &mut Dictionary, policy: &PlacementPolicy, scored: bool, number_rng: &mut R) -> Result<(), GenerationError> {
+    let mut failed_attempts: usize = 0;
 
     while !board.is_filled() {
-        let random_word = dictionary.get_random_word(number_rng);
-        let valid_placement = board.place_word_on_board(random_word, number_rng);
+        let random_word = match dictionary.get_random_word(number_rng) {
+            Some(word) => word,
+            None => return Err(GenerationError::DictionaryExhausted)
+        };
+
+        let valid_placement = if scored {
+            board.place_word_anywhere(random_word.clone(), policy, OverlapPreference::Prefer, number_rng)
+        } else {
+            board.place_word_on_board(random_word.clone(), policy, number_rng)
+        };
 
         if valid_placement.is_some() {
-            placements.push(valid_placement.unwrap());
+            // word can be on the board only once so there is no point in drawing it again
+            dictionary.remove_word(&random_word);
+            failed_attempts = 0;
+        } else {
+            failed_attempts += 1;
+
+            if failed_attempts >= MAX_FAILED_ATTEMPTS {
+                return Err(GenerationError::AttemptBudgetExceeded { attempts: failed_attempts });
+            }
         }
     }
 
-    return (board, placements);
+    return Ok(());
 }
 
-pub fn generate_random_id(length: usize, mut number_rng: &StdbRng) -> String {
+pub fn generate_random_id<R: Rng + ?Sized>(length: usize, number_rng: &mut R) -> String {
     pub const SAFE: [char; 63] = [
         '_', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g',
         'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
@@ -53,7 +321,7 @@ pub fn generate_random_id(length: usize, mut number_rng: &StdbRng) -> String {
     let mut generated_id: String = String::new();
 
     for _i in 0..length {
-        let random_character = SAFE.choose(&mut number_rng);
+        let random_character = SAFE.choose(number_rng);
         if random_character.is_some() {
             generated_id.push(random_character.unwrap().clone());
         }
@@ -67,4 +335,169 @@ macro_rules! id {
     ($size:tt, $rng:expr) => {
         $crate::generate_random_id($size, $rng)
     };
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use types::Direction;
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    fn east_only() -> PlacementPolicy {
+        PlacementPolicy { directions: vec![(Direction::East, 1)], ..PlacementPolicy::default() }
+    }
+
+    // first letters of the rows read COW down the first column
+    fn board_reading_cow() -> Board {
+        let mut board = Board::new(3, 3, String::new(), &Alphabet::english());
+        for (row, word) in ["CAT", "ORE", "WET"].iter().enumerate() {
+            board.place_word_in_direction(row, 0, word, &Direction::East);
+        }
+
+        return board;
+    }
+
+    fn to_rows(board: &Board) -> Vec<String> {
+        return board.grid.iter().map(|row| row.iter().map(|cell| cell.to_char()).collect()).collect();
+    }
+
+    fn animals() -> Vec<String> {
+        words(&["ANT", "APE", "BAT", "BEE", "CAT", "COD", "COW", "DOG", "EEL", "ELK", "EMU", "FOX", "GNU", "HEN", "OWL", "PIG", "RAM", "RAT", "YAK"])
+    }
+
+    #[test]
+    fn same_seed_and_options_give_the_same_board() {
+        let options = GeneratorOptions::default();
+
+        let first = generate_board_with_dictionary(5, 5, "HELLO".to_string(), &animals(), &options, &mut rng_from_seed(42)).unwrap();
+        let second = generate_board_with_dictionary(5, 5, "HELLO".to_string(), &animals(), &options, &mut rng_from_seed(42)).unwrap();
+
+        assert_eq!(to_rows(&first), to_rows(&second));
+        assert_eq!(first.placements, second.placements);
+        assert_eq!(first.solution_cells, second.solution_cells);
+    }
+
+    #[test]
+    fn fixed_seed_gives_the_golden_board() {
+        let board = generate_board_with_dictionary(5, 5, "HELLO".to_string(), &animals(), &GeneratorOptions::default(), &mut rng_from_seed(42)).unwrap();
+
+        // a change here means boards shared by seed are no longer reproduced for the players
+        assert_eq!(to_rows(&board), vec!["HMWOC", "KAYKA", "LRLEP", "EEEEE", "ELLBO"]);
+        let placed: Vec<&str> = board.placements.iter().map(|placement| placement.word.as_str()).collect();
+        assert_eq!(placed, vec!["ELK", "APE", "EEL", "BEE", "YAK", "RAM", "COW"]);
+        let solution: String = board.solution_cells.iter().map(|(row, col)| board.grid[*row][*col].to_char()).collect();
+        assert_eq!(solution, "HELLO");
+    }
+
+    #[test]
+    fn repair_replaces_the_word_with_a_second_reading() {
+        let options = GeneratorOptions { policy: east_only(), ..GeneratorOptions::default() };
+        let board = board_reading_cow();
+
+        let verification_words = words(&["CAT", "COW", "FIG", "ORE", "WET"]);
+        let issues = verify_board(&board, &verification_words, 3);
+        assert_eq!(issues.len(), 1);
+
+        let mut dictionary = Dictionary::from_words_sorted(words(&["FIG", "WET"]), &options.alphabet, 3, 3);
+        let repaired = repair_board(&board, &issues, &words(&["CAT", "ORE"]), &mut dictionary, &options, &mut rng_from_seed(7)).unwrap();
+
+        assert_eq!(to_rows(&repaired), vec!["CAT", "ORE", "FIG"]);
+        assert_eq!(verify_board(&repaired, &verification_words, 3), Vec::new());
+        assert!(!dictionary.words().contains(&"WET".to_string()));
+    }
+
+    #[test]
+    fn required_words_are_never_repaired() {
+        let options = GeneratorOptions { policy: east_only(), ..GeneratorOptions::default() };
+        let board = board_reading_cow();
+
+        let issues = verify_board(&board, &words(&["CAT", "COW", "ORE", "WET"]), 3);
+        let mut dictionary = Dictionary::from_words_sorted(words(&["FIG"]), &options.alphabet, 3, 3);
+
+        assert!(repair_board(&board, &issues, &words(&["CAT", "ORE", "WET"]), &mut dictionary, &options, &mut rng_from_seed(7)).is_none());
+    }
+
+    #[test]
+    fn unique_reading_works_with_the_english_catalogue() {
+        let options = GeneratorOptions { unique_reading: true, ..GeneratorOptions::default() };
+        let catalogue = Catalogue::from_directory(DICTIONARY_DIRECTORY, Some("en")).unwrap();
+
+        for seed in 0..3 {
+            let board = generate_board_from_catalogue(8, 8, "Hello world".to_string(), &catalogue, &options, &mut rng_from_seed(seed));
+
+            assert!(board.is_ok(), "seed {} gave {:?}", seed, board.err());
+        }
+    }
+
+    #[test]
+    fn every_required_word_is_placed_exactly_once() {
+        let required = words(&["CAT", "DOG", "OWL"]);
+        let board = generate_board_with_required_words(6, 6, "HELLO".to_string(), &required, Some(&animals()), &GeneratorOptions::default(), &mut rng_from_seed(42)).unwrap();
+
+        for word in &required {
+            assert_eq!(board.placements.iter().filter(|placement| &placement.word == word).count(), 1, "{} placed more than once", word);
+        }
+    }
+
+    #[test]
+    fn without_filler_only_the_required_words_are_placed() {
+        let options = GeneratorOptions { policy: east_only(), ..GeneratorOptions::default() };
+        let board = generate_board_with_required_words(3, 3, "XYZ".to_string(), &words(&["CAT", "DOG"]), None, &options, &mut rng_from_seed(42)).unwrap();
+
+        let mut placed: Vec<&str> = board.placements.iter().map(|placement| placement.word.as_str()).collect();
+        placed.sort();
+        assert_eq!(placed, vec!["CAT", "DOG"]);
+        assert_eq!(board.solution_cells.len(), 3);
+    }
+
+    #[test]
+    fn required_word_that_does_not_fit_is_reported() {
+        let options = GeneratorOptions { policy: east_only(), ..GeneratorOptions::default() };
+        let required = words(&["CAT", "DOG", "EMU", "OWL"]);
+
+        let result = generate_board_with_required_words(3, 3, String::new(), &required, None, &options, &mut rng_from_seed(42));
+        assert!(matches!(result, Err(GenerationError::WordsDidNotFit(did_not_fit)) if did_not_fit == vec!["OWL".to_string()]));
+
+        let result = generate_board_with_required_words(3, 3, String::new(), &words(&["ELEPHANT"]), None, &options, &mut rng_from_seed(42));
+        assert!(matches!(result, Err(GenerationError::WordsDidNotFit(did_not_fit)) if did_not_fit == vec!["ELEPHANT".to_string()]));
+    }
+
+    // letters shared by two or more placed words
+    fn crossings(board: &Board) -> usize {
+        let placed_letters: usize = board.placements.iter().map(|placement| placement.word.chars().count()).sum();
+        let covered: HashSet<(usize, usize)> = board.placements.iter().flat_map(|placement| placement.cells()).collect();
+
+        return placed_letters - covered.len();
+    }
+
+    #[test]
+    fn dense_strategy_crosses_more_words_than_greedy() {
+        let catalogue = Catalogue::from_directory(DICTIONARY_DIRECTORY, Some("en")).unwrap();
+        let greedy = GeneratorOptions { category: Some("animals".to_string()), ..GeneratorOptions::default() };
+        let dense = GeneratorOptions { strategy: GenerationStrategy::Dense, ..greedy.clone() };
+
+        for seed in 0..3 {
+            let greedy_board = generate_board_from_catalogue(8, 8, "HELLO".to_string(), &catalogue, &greedy, &mut rng_from_seed(seed)).unwrap();
+            let dense_board = generate_board_from_catalogue(8, 8, "HELLO".to_string(), &catalogue, &dense, &mut rng_from_seed(seed)).unwrap();
+
+            assert!(crossings(&dense_board) > crossings(&greedy_board), "seed {}: dense {} greedy {}", seed, crossings(&dense_board), crossings(&greedy_board));
+        }
+    }
+
+    #[test]
+    fn small_grid_with_enough_words_is_fillable() {
+        let fillability = is_board_fillable(3, 3, "XYZ".to_string(), &words(&["CAT", "DOG"]), &east_only(), &Alphabet::english());
+
+        assert_eq!(fillability, Ok(Fillability::Fillable));
+    }
+
+    #[test]
+    fn small_grid_without_enough_words_is_unfillable() {
+        let fillability = is_board_fillable(3, 3, String::new(), &words(&["CAT", "DOG"]), &east_only(), &Alphabet::english());
+
+        assert_eq!(fillability, Ok(Fillability::Unfillable));
+    }
+}
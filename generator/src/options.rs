@@ -0,0 +1,37 @@
+use crate::alphabet::Alphabet;
+use crate::policy::PlacementPolicy;
+use crate::shape::BoardShape;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum GenerationStrategy {
+    // Places random words on random empty cells and never revisits a placement
+    #[default]
+    Greedy,
+    // Depth first search over placements, undoing words that lead to unfillable gaps
+    Backtracking,
+    // Tries every cell and direction for each word and keeps the placement crossing the most letters
+    Dense,
+}
+
+// Order in which the leftover cells are read to reveal the solution
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SolutionOrder {
+    #[default]
+    RowMajor,
+    ColumnMajor,
+    // Clockwise from the top left corner towards the middle of the board
+    Spiral,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorOptions {
+    pub strategy: GenerationStrategy,
+    pub solution_order: SolutionOrder,
+    // Re-rolls the board until no unintended word or solution fragment can be read from it
+    pub unique_reading: bool,
+    pub policy: PlacementPolicy,
+    // Language of the dictionary is taken from the alphabet
+    pub alphabet: Alphabet,
+    pub category: Option<String>,
+    pub shape: BoardShape,
+}
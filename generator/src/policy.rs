@@ -0,0 +1,152 @@
+use std::str::FromStr;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use types::Direction;
+use crate::dictionary::MIN_WORD_LENGTH;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OverlapPreference {
+    // Picks the fitting direction sharing the fewest letters with already placed words
+    Avoid,
+    #[default]
+    Allow,
+    // Picks the fitting direction sharing the most letters with already placed words
+    Prefer,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlacementPolicy {
+    // Allowed directions with their relative weight, a direction with weight 0 is never used
+    pub directions: Vec<(Direction, u32)>,
+    pub min_word_length: usize,
+    // Words are never longer than the longer side of the board, None keeps that as the only limit
+    pub max_word_length: Option<usize>,
+    pub overlap: OverlapPreference,
+}
+
+impl Default for PlacementPolicy {
+    fn default() -> Self {
+        PlacementPolicy {
+            directions: Direction::ALL.iter().map(|d| (*d, 1)).collect(),
+            min_word_length: MIN_WORD_LENGTH,
+            max_word_length: None,
+            overlap: OverlapPreference::Allow,
+        }
+    }
+}
+
+impl PlacementPolicy {
+    pub fn word_length_range(&self, rows: usize, cols: usize) -> (usize, usize) {
+        let board_limit = std::cmp::max(rows, cols);
+        let max_word_length = match self.max_word_length {
+            Some(max_word_length) => std::cmp::min(max_word_length, board_limit),
+            None => board_limit
+        };
+
+        return (self.min_word_length, max_word_length);
+    }
+
+    pub fn allowed_directions(&self) -> Vec<Direction> {
+        return self.directions.iter().filter(|(_, weight)| *weight > 0).map(|(d, _)| *d).collect();
+    }
+
+    // Random order of the allowed directions where heavier directions tend to come first
+    pub fn weighted_directions<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<Direction> {
+        let allowed: Vec<(Direction, u32)> = self.directions.iter().filter(|(_, weight)| *weight > 0).cloned().collect();
+
+        return match allowed.choose_multiple_weighted(rng, allowed.len(), |(_, weight)| *weight as f64) {
+            Ok(directions) => directions.map(|(d, _)| *d).collect(),
+            Err(_) => Vec::new()
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn policy(&self) -> PlacementPolicy {
+        match self {
+            // Reading left to right and top to bottom only, suitable for kids
+            Difficulty::Easy => PlacementPolicy {
+                directions: vec![(Direction::East, 1), (Direction::South, 1)],
+                min_word_length: MIN_WORD_LENGTH,
+                max_word_length: Some(6),
+                overlap: OverlapPreference::Avoid,
+            },
+            Difficulty::Medium => PlacementPolicy {
+                directions: vec![
+                    (Direction::East, 3), (Direction::South, 3), (Direction::SouthEast, 2), (Direction::NorthEast, 2),
+                    (Direction::West, 1), (Direction::North, 1), (Direction::NorthWest, 1), (Direction::SouthWest, 1)
+                ],
+                min_word_length: MIN_WORD_LENGTH,
+                max_word_length: None,
+                overlap: OverlapPreference::Allow,
+            },
+            // Reversed and diagonal words are the hardest to spot
+            Difficulty::Hard => PlacementPolicy {
+                directions: vec![
+                    (Direction::East, 1), (Direction::South, 1), (Direction::SouthEast, 2), (Direction::NorthEast, 2),
+                    (Direction::West, 3), (Direction::North, 3), (Direction::NorthWest, 4), (Direction::SouthWest, 4)
+                ],
+                min_word_length: 4,
+                max_word_length: None,
+                overlap: OverlapPreference::Prefer,
+            },
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!("Unknown difficulty {}, expected one of easy, medium, hard", s))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::Board;
+    use crate::{generate_board_from_catalogue, rng_from_seed, Catalogue, GeneratorOptions, DICTIONARY_DIRECTORY};
+    use super::*;
+
+    fn board_for(difficulty: Difficulty) -> Board {
+        let catalogue = Catalogue::from_directory(DICTIONARY_DIRECTORY, Some("en")).unwrap();
+        let options = GeneratorOptions { policy: difficulty.policy(), category: Some("animals".to_string()), ..GeneratorOptions::default() };
+
+        return generate_board_from_catalogue(8, 8, "HELLO".to_string(), &catalogue, &options, &mut rng_from_seed(42)).unwrap();
+    }
+
+    #[test]
+    fn every_placement_follows_the_difficulty_directions() {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let allowed = difficulty.policy().allowed_directions();
+            let board = board_for(difficulty);
+
+            assert!(!board.placements.is_empty());
+            for placement in &board.placements {
+                assert!(allowed.contains(&placement.direction), "{:?} placed {} {:?}", difficulty, placement.word, placement.direction);
+            }
+        }
+    }
+
+    #[test]
+    fn easy_reads_forward_and_hard_does_not_have_to() {
+        let easy = board_for(Difficulty::Easy);
+        assert!(easy.placements.iter().all(|placement| matches!(placement.direction, Direction::East | Direction::South)));
+
+        let hard = board_for(Difficulty::Hard);
+        assert!(hard.placements.iter().any(|placement| !matches!(placement.direction, Direction::East | Direction::South)));
+        assert!(hard.placements.iter().all(|placement| placement.word.chars().count() >= 4));
+    }
+}
@@ -0,0 +1,142 @@
+use std::str::FromStr;
+use crate::error::GenerationError;
+
+// Outline of the board, cells outside of it are blocked and never hold a letter
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum BoardShape {
+    #[default]
+    Rectangle,
+    Circle,
+    Diamond,
+    Heart,
+    // rows x cols mask given by the caller, e.g. a letter drawn with from_pattern
+    Custom(Vec<Vec<bool>>),
+}
+
+impl BoardShape {
+    // One line per row, '#' marks a blocked cell and any other character an open one
+    pub fn from_pattern(pattern: &str) -> Result<BoardShape, GenerationError> {
+        let mask: Vec<Vec<bool>> = pattern.lines()
+            .map(|line| line.trim_end())
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().map(|c| c != '#').collect())
+            .collect();
+
+        let cols = mask.first().map(|r| r.len()).unwrap_or(0);
+        if cols == 0 || mask.iter().any(|r| r.len() != cols) {
+            return Err(GenerationError::InvalidMask("every row of the pattern needs the same number of cells".to_string()));
+        }
+
+        return Ok(BoardShape::Custom(mask));
+    }
+
+    pub fn mask(&self, rows: usize, cols: usize) -> Result<Vec<Vec<bool>>, GenerationError> {
+        let mask: Vec<Vec<bool>> = match self {
+            BoardShape::Rectangle => vec![vec![true; cols]; rows],
+            BoardShape::Custom(mask) => {
+                if mask.len() != rows || mask.iter().any(|r| r.len() != cols) {
+                    return Err(GenerationError::InvalidMask(format!("mask does not match the {}x{} board", rows, cols)));
+                }
+
+                mask.clone()
+            },
+            shape => (0..rows).map(|r| (0..cols).map(|c| {
+                // cell centre scaled to -1.0..1.0 with y pointing up
+                let x = (c as f32 + 0.5) / cols as f32 * 2.0 - 1.0;
+                let y = 1.0 - (r as f32 + 0.5) / rows as f32 * 2.0;
+
+                shape.contains(x, y)
+            }).collect()).collect()
+        };
+
+        if !mask.iter().flatten().any(|open| *open) {
+            return Err(GenerationError::InvalidMask("board has no open cells".to_string()));
+        }
+
+        return Ok(mask);
+    }
+
+    fn contains(&self, x: f32, y: f32) -> bool {
+        match self {
+            BoardShape::Circle => x * x + y * y <= 1.0,
+            BoardShape::Diamond => x.abs() + y.abs() <= 1.0,
+            BoardShape::Heart => {
+                // heart curve (x² + y² - 1)³ = x²y³ stretched to fill the square
+                let (x, y) = (x * 1.25, y * 1.25 + 0.2);
+                (x * x + y * y - 1.0).powi(3) - x * x * y.powi(3) <= 0.0
+            },
+            _ => true
+        }
+    }
+}
+
+impl FromStr for BoardShape {
+    type Err = String;
+
+    fn from_str(shape: &str) -> Result<Self, Self::Err> {
+        match shape.to_lowercase().as_str() {
+            "rectangle" => Ok(BoardShape::Rectangle),
+            "circle" => Ok(BoardShape::Circle),
+            "diamond" => Ok(BoardShape::Diamond),
+            "heart" => Ok(BoardShape::Heart),
+            _ => Err(format!("Shape {} is not supported", shape))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{Board, Cell};
+    use crate::{generate_board_from_catalogue, rng_from_seed, Catalogue, GeneratorOptions, DICTIONARY_DIRECTORY};
+    use super::*;
+
+    fn generate(rows: usize, cols: usize, message: &str, shape: BoardShape) -> Result<Board, GenerationError> {
+        let catalogue = Catalogue::from_directory(DICTIONARY_DIRECTORY, Some("en")).unwrap();
+        let options = GeneratorOptions { shape, category: Some("animals".to_string()), ..GeneratorOptions::default() };
+
+        return generate_board_from_catalogue(rows, cols, message.to_string(), &catalogue, &options, &mut rng_from_seed(42));
+    }
+
+    #[test]
+    fn blocked_cells_stay_blocked() {
+        for shape in [BoardShape::Circle, BoardShape::Diamond, BoardShape::Heart] {
+            let mask = shape.mask(10, 10).unwrap();
+            let board = generate(10, 10, "HELLO", shape.clone()).unwrap();
+
+            for (row, cells) in board.grid.iter().enumerate() {
+                for (col, cell) in cells.iter().enumerate() {
+                    assert_eq!(*cell == Cell::Blocked, !mask[row][col], "{:?} at {}x{}", shape, row, col);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn no_placement_crosses_a_blocked_cell() {
+        let shape = BoardShape::from_pattern("\
+            ..##..\n\
+            ......\n\
+            ......\n\
+            ......\n\
+            ..##..\n\
+            ..##..").unwrap();
+        let mask = shape.mask(6, 6).unwrap();
+        let board = generate(6, 6, "HELLO", shape).unwrap();
+
+        for placement in &board.placements {
+            assert!(placement.cells().iter().all(|(row, col)| mask[*row][*col]), "{} crosses a blocked cell", placement.word);
+        }
+    }
+
+    #[test]
+    fn mask_smaller_than_the_solution_is_rejected() {
+        let shape = BoardShape::from_pattern("\
+            ######\n\
+            #....#\n\
+            ######").unwrap();
+
+        let result = generate(3, 6, "HELLO WORLD", shape);
+
+        assert!(matches!(result, Err(GenerationError::MessageTooLong { solution_length: 10, cells: 4 })));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::error::GenerationError;
+use crate::options::GeneratorOptions;
+
+// Smallest side accepted by the game, anything narrower leaves no room for the hidden words
+pub const MIN_GRID_SIDE: usize = 6;
+pub const MAX_GRID_SIDE: usize = types::MAX_GRID_SIDE;
+// Empty cells a hidden word takes on average once crossings with other words are accounted for
+const AVERAGE_WORD_CELLS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoSize {
+    // Number of hidden words the grid should have room for next to the solution
+    pub target_words: usize,
+    // Columns divided by rows, without it the grid stays as close to a square as possible
+    pub aspect_ratio: Option<f32>,
+    pub max_side: usize,
+}
+
+impl Default for AutoSize {
+    fn default() -> Self {
+        AutoSize { target_words: 10, aspect_ratio: None, max_side: MAX_GRID_SIDE }
+    }
+}
+
+// Picks the grid with the fewest cells that holds the solution and the target number of words
+pub fn auto_grid_size(message: &str, sizing: &AutoSize, options: &GeneratorOptions) -> Result<(usize, usize), GenerationError> {
+    let solution_length = options.alphabet.normalize_message(message).chars().count();
+    // target_words comes from the request, so an absurd value must not overflow
+    let required_cells = solution_length.saturating_add(sizing.target_words.saturating_mul(AVERAGE_WORD_CELLS));
+
+    let mut candidates: Vec<(usize, usize)> = Vec::new();
+
+    for rows in MIN_GRID_SIDE..=sizing.max_side {
+        match sizing.aspect_ratio {
+            Some(aspect_ratio) if aspect_ratio > 0.0 => {
+                let cols = (rows as f32 * aspect_ratio).round() as usize;
+
+                if cols >= MIN_GRID_SIDE && cols <= sizing.max_side {
+                    candidates.push((rows, cols));
+                }
+            },
+            _ => {
+                candidates.push((rows, rows));
+
+                if rows < sizing.max_side {
+                    candidates.push((rows, rows + 1));
+                }
+            }
+        }
+    }
+
+    return candidates.into_iter()
+        .filter(|(rows, cols)| open_cells(*rows, *cols, options) >= required_cells)
+        .min_by_key(|(rows, cols)| (open_cells(*rows, *cols, options), rows.abs_diff(*cols)))
+        .ok_or(GenerationError::NoGridSize { required_cells, max_side: sizing.max_side });
+}
+
+// Shaped boards lose their blocked cells, a custom mask only fits the size it was drawn for
+fn open_cells(rows: usize, cols: usize, options: &GeneratorOptions) -> usize {
+    match options.shape.mask(rows, cols) {
+        Ok(mask) => mask.iter().flatten().filter(|open| **open).count(),
+        Err(_) => 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chosen_size_fits_the_message_and_words() {
+        let options = GeneratorOptions::default();
+        let sizing = AutoSize::default();
+
+        let (rows, cols) = auto_grid_size("Hello world", &sizing, &options).unwrap();
+
+        // 10 letters of the solution and 10 words of 5 cells
+        assert!(rows * cols >= 60);
+        assert!((rows - 1) * (cols - 1) < 60);
+        assert!(rows.abs_diff(cols) <= 1);
+    }
+
+    #[test]
+    fn aspect_ratio_is_honoured() {
+        let options = GeneratorOptions::default();
+        let sizing = AutoSize { aspect_ratio: Some(2.0), ..AutoSize::default() };
+
+        let (rows, cols) = auto_grid_size("Hello world", &sizing, &options).unwrap();
+
+        assert_eq!(cols, rows * 2);
+        assert!(rows * cols >= 60);
+    }
+
+    #[test]
+    fn oversized_message_has_no_grid() {
+        let options = GeneratorOptions::default();
+        let message = "A".repeat(MAX_GRID_SIDE * MAX_GRID_SIDE);
+
+        let result = auto_grid_size(&message, &AutoSize::default(), &options);
+
+        assert!(matches!(result, Err(GenerationError::NoGridSize { max_side: MAX_GRID_SIDE, .. })));
+    }
+
+    #[test]
+    fn huge_word_target_does_not_overflow() {
+        let options = GeneratorOptions::default();
+        let sizing = AutoSize { target_words: usize::MAX, ..AutoSize::default() };
+
+        let result = auto_grid_size("Hello world", &sizing, &options);
+
+        assert_eq!(result, Err(GenerationError::NoGridSize { required_cells: usize::MAX, max_side: MAX_GRID_SIDE }));
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::{HashMap, HashSet};
+use types::{Cell, Direction};
+use crate::dictionary::Dictionary;
+use crate::options::SolutionOrder;
+use crate::board::reading_order;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub word: String,
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+}
+
+impl Occurrence {
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        return (0..self.word.chars().count()).map(|depth| (
+            (self.row as isize + self.direction.row_step() * depth as isize) as usize,
+            (self.col as isize + self.direction.col_step() * depth as isize) as usize
+        )).collect();
+    }
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: HashMap<char, usize>,
+    // set when the path from the root spells a whole word
+    word: Option<String>,
+}
+
+// Finds words of a dictionary in any grid. Words are matched as written, so they need to be normalized
+// by the same alphabet as the grid letters
+pub struct Solver {
+    nodes: Vec<TrieNode>,
+}
+
+impl Solver {
+    pub fn new(words: &Vec<String>) -> Self {
+        let mut solver = Solver { nodes: vec![TrieNode::default()] };
+
+        for word in words {
+            solver.insert(word);
+        }
+
+        return solver;
+    }
+
+    pub fn from_dictionary(dictionary: &Dictionary) -> Self {
+        return Solver::new(dictionary.words());
+    }
+
+    fn insert(&mut self, word: &str) {
+        let mut node: usize = 0;
+
+        for letter in word.chars() {
+            node = match self.nodes[node].children.get(&letter) {
+                Some(child) => *child,
+                None => {
+                    self.nodes.push(TrieNode::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(letter, child);
+
+                    child
+                }
+            };
+        }
+
+        if node != 0 {
+            self.nodes[node].word = Some(word.to_string());
+        }
+    }
+
+    // Every occurrence of every word starting in any cell and read in any of the 8 directions.
+    // Empty and blocked cells end the line, words never jump over them
+    pub fn find_words(&self, grid: &[Vec<Cell>]) -> Vec<Occurrence> {
+        let mut occurrences: Vec<Occurrence> = Vec::new();
+
+        for row in 0..grid.len() {
+            for col in 0..grid[row].len() {
+                for direction in Direction::ALL {
+                    self.find_words_from(grid, row, col, direction, &mut occurrences);
+                }
+            }
+        }
+
+        return occurrences;
+    }
+
+    fn find_words_from(&self, grid: &[Vec<Cell>], row: usize, col: usize, direction: Direction, occurrences: &mut Vec<Occurrence>) {
+        let mut node: usize = 0;
+        let (mut irow, mut icol) = (row as isize, col as isize);
+
+        while irow >= 0 && icol >= 0 {
+            let letter = match grid.get(irow as usize).and_then(|r| r.get(icol as usize)).and_then(|cell| cell.letter()) {
+                Some(letter) => letter,
+                None => return
+            };
+
+            node = match self.nodes[node].children.get(&letter) {
+                Some(child) => *child,
+                None => return
+            };
+
+            if let Some(word) = &self.nodes[node].word {
+                occurrences.push(Occurrence { word: word.clone(), row, col, direction });
+            }
+
+            irow += direction.row_step();
+            icol += direction.col_step();
+        }
+    }
+}
+
+// Letters not covered by any of the occurrences, read in given order. For a solved board these spell the solution
+pub fn leftover_letters(grid: &[Vec<Cell>], occurrences: &[Occurrence], order: SolutionOrder) -> (String, Vec<(usize, usize)>) {
+    let covered: HashSet<(usize, usize)> = occurrences.iter().flat_map(|o| o.cells()).collect();
+    let rows = grid.len();
+    let cols = grid.first().map(|r| r.len()).unwrap_or(0);
+
+    let mut letters = String::new();
+    let mut cells: Vec<(usize, usize)> = Vec::new();
+
+    for (row, col) in reading_order(rows, cols, order) {
+        if covered.contains(&(row, col)) {
+            continue;
+        }
+
+        if let Some(letter) = grid[row][col].letter() {
+            letters.push(letter);
+            cells.push((row, col));
+        }
+    }
+
+    return (letters, cells);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{generate_board_with_dictionary, rng_from_seed, GeneratorOptions};
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Vec<Vec<Cell>> {
+        return rows.iter().map(|row| row.chars().map(Cell::Letter).collect()).collect();
+    }
+
+    fn words(words: &[&str]) -> Vec<String> {
+        return words.iter().map(|w| w.to_string()).collect();
+    }
+
+    #[test]
+    fn words_are_found_in_all_directions() {
+        let grid = grid(&[
+            "TXTXT",
+            "XAAAX",
+            "TACAT",
+            "XAAAX",
+            "TXTXT",
+        ]);
+
+        let occurrences = Solver::new(&words(&["CAT"])).find_words(&grid);
+
+        let directions: HashSet<Direction> = occurrences.iter().map(|o| o.direction).collect();
+        assert_eq!(occurrences.len(), 8);
+        assert_eq!(directions, Direction::ALL.iter().copied().collect());
+        assert!(occurrences.iter().all(|o| (o.row, o.col) == (2, 2)));
+    }
+
+    #[test]
+    fn overlapping_occurrences_are_all_found() {
+        let occurrences = Solver::new(&words(&["CAT", "CATS", "TAT"])).find_words(&grid(&["CATAT"]));
+
+        let found: Vec<(&str, usize, Direction)> = occurrences.iter().map(|o| (o.word.as_str(), o.col, o.direction)).collect();
+        assert_eq!(found.len(), 3);
+        assert!(found.contains(&("CAT", 0, Direction::East)));
+        assert!(found.contains(&("TAT", 2, Direction::East)));
+        assert!(found.contains(&("TAT", 4, Direction::West)));
+    }
+
+    #[test]
+    fn words_running_off_the_edge_are_not_found() {
+        let grid = grid(&[
+            "XCA",
+            "XXA",
+        ]);
+
+        assert_eq!(Solver::new(&words(&["CAT", "CAAT"])).find_words(&grid), Vec::new());
+    }
+
+    #[test]
+    fn empty_cells_end_the_word() {
+        let mut grid = grid(&["CAT"]);
+        grid[0][1] = Cell::Empty;
+
+        assert_eq!(Solver::new(&words(&["CAT"])).find_words(&grid), Vec::new());
+    }
+
+    #[test]
+    fn leftover_letters_are_the_solution_of_a_generated_board() {
+        let options = GeneratorOptions { unique_reading: true, ..GeneratorOptions::default() };
+        let animals = words(&["ANT", "APE", "BAT", "BEE", "CAT", "COD", "COW", "DOG", "EEL", "ELK", "EMU", "FOX", "GNU", "HEN", "OWL", "PIG", "RAM", "RAT", "YAK"]);
+        let board = generate_board_with_dictionary(6, 6, "HELLO".to_string(), &animals, &options, &mut rng_from_seed(42)).unwrap();
+
+        let placed: Vec<String> = board.placements.iter().map(|placement| placement.word.clone()).collect();
+        let occurrences = Solver::new(&placed).find_words(&board.grid);
+
+        assert_eq!(leftover_letters(&board.grid, &occurrences, options.solution_order), (board.solution.clone(), board.solution_cells.clone()));
+    }
+}
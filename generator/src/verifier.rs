@@ -0,0 +1,133 @@
+use std::collections::HashSet;
+use types::{Board, Direction};
+use crate::solver::Solver;
+
+// Shorter pieces of the solution are too common to be considered a spoiler
+const MIN_SOLUTION_FRAGMENT_LENGTH: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationIssue {
+    UnintendedWord { word: String, row: usize, col: usize, direction: Direction },
+    SolutionFragment { fragment: String, row: usize, col: usize, direction: Direction },
+}
+
+impl VerificationIssue {
+    // Cells the unintended reading runs through
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        let (text, row, col, direction) = match self {
+            VerificationIssue::UnintendedWord { word, row, col, direction } => (word, row, col, direction),
+            VerificationIssue::SolutionFragment { fragment, row, col, direction } => (fragment, row, col, direction),
+        };
+
+        return (0..text.chars().count()).map(|depth| (
+            (*row as isize + direction.row_step() * depth as isize) as usize,
+            (*col as isize + direction.col_step() * depth as isize) as usize
+        )).collect();
+    }
+}
+
+// Scans every line of the finished board and reports readings other than the placed words and the solution.
+// Words are expected in the same form as on the grid, i.e. normalized by the board's alphabet. Words shorter
+// than min_word_length could never be placed, so they are not looked for either
+pub fn verify_board(board: &Board, words: &[String], min_word_length: usize) -> Vec<VerificationIssue> {
+    let solution_cells: HashSet<(usize, usize)> = board.solution_cells.iter().cloned().collect();
+    let dictionary: Vec<String> = words.iter()
+        .filter(|w| w.chars().count() >= min_word_length)
+        .cloned()
+        .collect();
+    let placed_cells: Vec<HashSet<(usize, usize)>> = board.placements.iter().map(|p| p.cells().into_iter().collect()).collect();
+
+    let mut issues: Vec<VerificationIssue> = Vec::new();
+
+    // parts of placed words, e.g. CAT inside CATALOG, are not a second reading
+    for occurrence in Solver::new(&dictionary).find_words(&board.grid) {
+        let cells = occurrence.cells();
+        let is_placed_word = placed_cells.iter().any(|placed| cells.iter().all(|cell| placed.contains(cell)));
+
+        if !is_placed_word {
+            issues.push(VerificationIssue::UnintendedWord {
+                word: occurrence.word, row: occurrence.row, col: occurrence.col, direction: occurrence.direction
+            });
+        }
+    }
+
+    let solution: Vec<char> = board.solution.chars().collect();
+    let fragment_length = MIN_SOLUTION_FRAGMENT_LENGTH.min(solution.len());
+    if fragment_length == 0 {
+        return issues;
+    }
+
+    let fragments: HashSet<String> = solution.windows(fragment_length).map(|w| w.iter().collect()).collect();
+
+    for row in 0..board.rows {
+        for col in 0..board.cols {
+            for direction in Direction::ALL.iter() {
+                let cells: Vec<(usize, usize)> = line_cells(board, row, col, direction).into_iter().take(fragment_length).collect();
+                if cells.len() < fragment_length {
+                    continue;
+                }
+
+                let line: String = cells.iter().map(|(r, c)| board.grid[*r][*c].to_char()).collect();
+                let is_solution_reading = cells.iter().all(|cell| solution_cells.contains(cell));
+
+                if fragments.contains(&line) && !is_solution_reading {
+                    issues.push(VerificationIssue::SolutionFragment {
+                        fragment: line, row, col, direction: *direction
+                    });
+                }
+            }
+        }
+    }
+
+    return issues;
+}
+
+fn line_cells(board: &Board, row: usize, col: usize, direction: &Direction) -> Vec<(usize, usize)> {
+    let mut cells: Vec<(usize, usize)> = Vec::new();
+    let mut irow: isize = row as isize;
+    let mut icol: isize = col as isize;
+
+    // blocked cells split the line, letters on both sides of them are never read together
+    while irow >= 0 && icol >= 0 && (irow as usize) < board.rows && (icol as usize) < board.cols && board.is_open(irow as usize, icol as usize) {
+        cells.push((irow as usize, icol as usize));
+        irow += direction.row_step();
+        icol += direction.col_step();
+    }
+
+    return cells;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::Alphabet;
+    use crate::board::BoardTrait;
+    use super::*;
+
+    // first letters of the rows read COW down the first column
+    fn board_with_duplicate_reading() -> Board {
+        let mut board = Board::new(3, 3, String::new(), &Alphabet::english());
+        for (row, word) in ["CAT", "ORE", "WET"].iter().enumerate() {
+            board.place_word_in_direction(row, 0, word, &Direction::East);
+        }
+
+        return board;
+    }
+
+    fn words() -> Vec<String> {
+        ["CAT", "COW", "ORE", "WET"].iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn reading_across_placed_words_is_reported() {
+        let issues = verify_board(&board_with_duplicate_reading(), &words(), 3);
+
+        let expected = VerificationIssue::UnintendedWord { word: "COW".to_string(), row: 0, col: 0, direction: Direction::South };
+        assert_eq!(issues, vec![expected.clone()]);
+        assert_eq!(expected.cells(), vec![(0, 0), (1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn words_below_minimum_length_are_not_looked_for() {
+        assert_eq!(verify_board(&board_with_duplicate_reading(), &words(), 4), Vec::new());
+    }
+}
@@ -1,10 +1,22 @@
-use spacetimedb::{table, reducer, Table, ReducerContext, Identity, Timestamp};
-use generator::{generate_board_with_dictionary, id};
+mod room;
+mod scoring;
+mod session;
+mod timed;
+
+use spacetimedb::{table, reducer, Table, ReducerContext, Identity, Timestamp, SpacetimeType, ScheduleAt, TimeDuration};
+use spacetimedb::rand::Rng;
+use generator::{generate_board_with_dictionary, generate_board_with_required_words, id, rng_from_seed, Alphabet, BoardShape, Difficulty, GeneratorOptions};
+use types::{next_hint, Board, Direction, HintLevel, Placement, PuzzleFile, Selection, MAX_SOLUTION_ATTEMPTS};
+use crate::room::{claiming_players, finishing_players, pick_winner, progress_players, remaining_players, word_claimed, words_found_by, RoomMode};
+use crate::scoring::{solution_bonus, word_points};
+use crate::session::{close_player_session, disconnect_player, find_player_session, finish_expired_session, finish_player_session, finish_sessions, join_session, reconnect_player, session_id, start_timed_session, SessionStore};
+use crate::timed::TimedMode;
 
 #[table(name = word, public)]
 pub struct WordPlacementsDatabaseModel {
     #[primary_key]
     pub id: String,
+    #[index(btree)]
     pub board_id: String,
     pub direction: String,
     pub start_row: u8,
@@ -12,6 +24,12 @@ pub struct WordPlacementsDatabaseModel {
     pub word: String,
 }
 
+#[derive(SpacetimeType, Clone)]
+pub struct CellPosition {
+    pub row: u8,
+    pub col: u8,
+}
+
 #[table(name = board, public)]
 pub struct BoardDatabaseModel {
     #[primary_key]
@@ -23,20 +41,114 @@ pub struct BoardDatabaseModel {
     pub message: String,
     pub solution: String,
     pub grid: String,
+    pub solution_cells: Vec<CellPosition>,
+    // cells outside of the board's shape, empty for rectangular boards
+    pub blocked_cells: Vec<CellPosition>,
+    pub seed: u64,
+    pub language: String,
+}
+
+#[derive(SpacetimeType, Clone)]
+pub struct HintRecord {
+    pub word: String,
+    // 1 first letter, 2 direction, 3 full placement
+    pub level: u8,
+    pub start: CellPosition,
+    pub direction: Option<String>,
+    pub end: Option<CellPosition>,
+    pub given_at: Timestamp,
+}
+
+#[derive(SpacetimeType, Clone)]
+pub struct SolutionAttempt {
+    pub guess: String,
+    pub correct: bool,
+    pub guessed_at: Timestamp,
 }
 
 #[table(name = game_session, public, index(name = board_id_played_by, btree(columns = [board_id, played_by])))]
+#[derive(Clone)]
 pub struct GameSessionDatabaseModel {
     #[primary_key]
     pub id: String,
     pub board_id: String,
     pub started_date: Timestamp,
+    #[index(btree)]
     pub played_by: Identity,
     pub finished: bool,
     pub is_online: bool,
-    pub found_words: String
+    // replaced by the found_word table, only sessions not yet moved by migrate_found_words still use it
+    pub found_words: String,
+    pub hints: Vec<HintRecord>,
+    pub score: u32,
+    pub solution_attempts: Vec<SolutionAttempt>,
+    // progress, total is the number of words placed on the board
+    pub words_found: u32,
+    pub words_total: u32,
+    // set while the player plays the board in a room
+    pub room_id: Option<String>,
+    // end of a timed game, words found later do not count
+    pub deadline: Option<Timestamp>,
 }
 
+#[table(name = session_deadline, scheduled(finish_timed_session))]
+pub struct SessionDeadlineDatabaseModel {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+    pub board_id: String,
+    pub played_by: Identity,
+}
+
+// One row per player who ever connected, others subscribe to it to see who plays what
+#[table(name = presence, public)]
+pub struct PresenceDatabaseModel {
+    #[primary_key]
+    pub identity: Identity,
+    pub is_online: bool,
+    pub connected_date: Timestamp,
+    pub last_seen: Timestamp,
+    // board the player has open, None between games. Kept while offline so reconnecting brings it back
+    pub board_id: Option<String>,
+    // sessions the last disconnect took offline, the next connect brings back exactly these
+    pub disconnected_sessions: Vec<String>,
+}
+
+#[table(name = game_room, public)]
+pub struct RoomDatabaseModel {
+    #[primary_key]
+    pub id: String,
+    #[index(btree)]
+    pub board_id: String,
+    // "coop" or "versus"
+    pub mode: String,
+    pub created_by: Identity,
+    pub players: Vec<Identity>,
+    pub started_date: Timestamp,
+    pub ended_date: Option<Timestamp>,
+    // highest score once the room ended, nobody on a tie
+    pub winner: Option<Identity>,
+}
+
+#[table(name = found_word, public)]
+pub struct FoundWordDatabaseModel {
+    // "{session_id}-{word}" so every session finds a word only once
+    #[primary_key]
+    pub id: String,
+    #[index(btree)]
+    pub session_id: String,
+    #[index(btree)]
+    pub board_id: String,
+    pub word: String,
+    pub found_at: Timestamp,
+    pub start: CellPosition,
+    pub end: CellPosition,
+    pub found_by: Identity,
+}
+
+// replaced by the dictionary_word table, which allows the same word in several languages. Words are
+// moved from here by migrate_dictionary
 #[table(name = dictionary, private)]
 pub struct DictionaryDatabaseModel {
     #[primary_key]
@@ -45,6 +157,20 @@ pub struct DictionaryDatabaseModel {
     pub category: Option<String>
 }
 
+#[table(name = dictionary_word, private)]
+pub struct DictionaryWordDatabaseModel {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    // same word may exist in several languages
+    pub word: String,
+    #[index(btree)]
+    pub language: String,
+    pub category: Option<String>
+}
+
+const DEFAULT_LANGUAGE: &str = "en";
+
 fn validate_board_params(rows: u8, cols: u8) -> Result<(), String> {
     if rows > 5 && cols > 5 {
         Ok(())
@@ -53,40 +179,148 @@ fn validate_board_params(rows: u8, cols: u8) -> Result<(), String> {
     }
 }
 
+// options stay separate reducer arguments so existing clients keep calling the reducers the same way
 #[reducer]
-pub fn generate_new_board(reducer_context: &ReducerContext, rows: u8, cols: u8, message: String) -> Result<(), String> {
+#[allow(clippy::too_many_arguments)]
+pub fn generate_new_board(reducer_context: &ReducerContext, rows: u8, cols: u8, message: String, seed: Option<u64>, difficulty: Option<String>, language: Option<String>, category: Option<String>, shape: Option<String>) -> Result<(), String> {
     let valid_params = validate_board_params(rows, cols);
 
     if valid_params.is_err() {
         return Err(valid_params.unwrap_err())
     }
 
-    let number_rng = reducer_context.rng();
+    let mut options = GeneratorOptions::default();
+    if let Some(difficulty) = difficulty {
+        options.policy = difficulty.parse::<Difficulty>()?.policy();
+    }
+    if let Some(shape) = shape {
+        options.shape = shape.parse::<BoardShape>()?;
+    }
+
+    let language: String = language.unwrap_or(DEFAULT_LANGUAGE.to_string());
+    options.alphabet = match Alphabet::for_language(&language) {
+        Some(alphabet) => alphabet,
+        None => return Err(format!("Language {} is not supported", language))
+    };
+
+    let mut number_rng = reducer_context.rng();
+
+    // Same seed, dictionary and message always generate the same board so seed is stored to share it
+    let seed: u64 = seed.unwrap_or_else(|| number_rng.r#gen());
+    let mut seeded_rng = rng_from_seed(seed);
 
-    let words = reducer_context.db.dictionary().iter();
-    let words_dictionary: Vec<String> = words.map(|w|w.word).collect::<Vec<String>>();
+    let words = reducer_context.db.dictionary_word().language().filter(language.as_str());
+    let words_dictionary: Vec<String> = words
+        .filter(|w| category.is_none() || w.category == category)
+        .map(|w|w.word).collect::<Vec<String>>();
+
+    if words_dictionary.is_empty() {
+        return Err(format!("Dictionary has no words for language {} and category {}", language, category.unwrap_or("any".to_string())))
+    }
 
     // This is called from the module
-    let (generated_board, placements) = generate_board_with_dictionary(rows as usize, cols as usize, message.clone(), &words_dictionary, number_rng);
+    let generated_board = match generate_board_with_dictionary(rows as usize, cols as usize, message.clone(), &words_dictionary, &options, &mut seeded_rng) {
+        Ok(generated) => generated,
+        Err(error) => return Err(error.to_string())
+    };
+
+    insert_generated_board(reducer_context, &mut number_rng, generated_board, seed, language)?;
 
+    return Ok(());
+}
+
+#[reducer]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_themed_board(reducer_context: &ReducerContext, rows: u8, cols: u8, message: String, words: Vec<String>, top_up: bool, seed: Option<u64>, difficulty: Option<String>, language: Option<String>, category: Option<String>, shape: Option<String>) -> Result<(), String> {
+    validate_board_params(rows, cols)?;
+
+    if words.is_empty() {
+        return Err("Themed board needs at least one word".to_string())
+    }
+
+    let mut options = GeneratorOptions::default();
+    if let Some(difficulty) = difficulty {
+        options.policy = difficulty.parse::<Difficulty>()?.policy();
+    }
+    if let Some(shape) = shape {
+        options.shape = shape.parse::<BoardShape>()?;
+    }
+
+    let language: String = language.unwrap_or(DEFAULT_LANGUAGE.to_string());
+    options.alphabet = match Alphabet::for_language(&language) {
+        Some(alphabet) => alphabet,
+        None => return Err(format!("Language {} is not supported", language))
+    };
+
+    let mut number_rng = reducer_context.rng();
+
+    let seed: u64 = seed.unwrap_or_else(|| number_rng.r#gen());
+    let mut seeded_rng = rng_from_seed(seed);
+
+    // Without top up only the caller's words are used, so they have to cover the whole grid except the solution
+    let filler_words: Option<Vec<String>> = if top_up {
+        Some(reducer_context.db.dictionary_word().language().filter(language.as_str())
+            .filter(|w| category.is_none() || w.category == category)
+            .map(|w| w.word).collect::<Vec<String>>())
+    } else {
+        None
+    };
+
+    let generated_board = match generate_board_with_required_words(rows as usize, cols as usize, message, &words, filler_words.as_ref(), &options, &mut seeded_rng) {
+        Ok(generated) => generated,
+        Err(error) => return Err(error.to_string())
+    };
+
+    insert_generated_board(reducer_context, &mut number_rng, generated_board, seed, language)?;
+
+    return Ok(());
+}
+
+// Puzzles exported by another backend keep their letters, only a new board id is assigned
+#[reducer]
+pub fn import_puzzle(reducer_context: &ReducerContext, puzzle: String) -> Result<(), String> {
+    let puzzle_file = PuzzleFile::from_json(&puzzle).map_err(|error| error.to_string())?;
+    let board = puzzle_file.to_board().map_err(|error| error.to_string())?;
+
+    // anything above u8 would wrap around and could pass the check
+    let rows = u8::try_from(board.rows).map_err(|_| format!("Board with {} rows is too large", board.rows))?;
+    let cols = u8::try_from(board.cols).map_err(|_| format!("Board with {} cols is too large", board.cols))?;
+    validate_board_params(rows, cols)?;
+
+    let language: String = puzzle_file.language.unwrap_or(DEFAULT_LANGUAGE.to_string());
+    let seed: u64 = puzzle_file.metadata.seed.unwrap_or(0);
+
+    let mut number_rng = reducer_context.rng();
+    insert_generated_board(reducer_context, &mut number_rng, board, seed, language)?;
+
+    return Ok(());
+}
+
+fn insert_generated_board<R: Rng + ?Sized>(reducer_context: &ReducerContext, number_rng: &mut R, generated_board: Board, seed: u64, language: String) -> Result<String, String> {
     // Board id generated by custom nanoid ensures id is easier to remeber and read
     let board_id = id!(10, number_rng);
-
-    // Reduce 2 dimensional array into a string
-    let grid_string: String = generated_board.grid.iter()
-        .map(|r| r.iter().map(|c| format!("{}", c)).collect::<String>())
-        .rfold(String::from(""), |data, r| format!("{}{}", r, data));
+    let grid_string: String = generated_board.to_grid_string().map_err(|error| error.to_string())?;
+    let blocked_cells: Vec<CellPosition> = generated_board.blocked_cells().iter().map(|(row, col)| CellPosition {
+        row: *row as u8, col: *col as u8
+    }).collect();
 
     let board_model = BoardDatabaseModel {
         id: board_id.clone(),
         created_date: reducer_context.timestamp,
         created_by: reducer_context.sender,
         solution: generated_board.solution,
+        solution_cells: generated_board.solution_cells.iter().map(|(row, col)| CellPosition {
+            row: *row as u8, col: *col as u8
+        }).collect(),
+        blocked_cells,
         grid: grid_string,
-        message: message, cols: cols, rows: rows
+        message: generated_board.message,
+        cols: generated_board.cols as u8,
+        rows: generated_board.rows as u8,
+        seed, language
     };
 
-    let words_placement_model: Vec<WordPlacementsDatabaseModel> = placements.iter().map(| placement | WordPlacementsDatabaseModel {
+    let words_placement_model: Vec<WordPlacementsDatabaseModel> = generated_board.placements.iter().map(| placement | WordPlacementsDatabaseModel {
         id: format!("{}-{}", board_id.clone(), &placement.word),
         start_row: placement.row as u8,
         start_col: placement.col as u8,
@@ -101,115 +335,529 @@ pub fn generate_new_board(reducer_context: &ReducerContext, rows: u8, cols: u8,
         reducer_context.db.word().insert(placement_model);
     }
 
-    return Ok(());
+    return Ok(board_id);
 }
 
-#[reducer]
-pub fn join_game(reducer_context: &ReducerContext, board_id: String) -> Result<(), String> {
-    let board_id_played_by_index: spacetimedb::RangedIndex<_, (String, Identity), _> = reducer_context.db.game_session().board_id_played_by();
+#[reducer(client_connected)]
+pub fn client_connected(reducer_context: &ReducerContext) {
+    let previous = reducer_context.db.presence().identity().find(reducer_context.sender);
+    let disconnected: Vec<String> = previous.as_ref().map(|presence| presence.disconnected_sessions.clone()).unwrap_or_default();
+
+    reconnect_player(reducer_context, &disconnected);
+
+    // the open board comes back only together with its session
+    let board_id: Option<String> = previous.as_ref()
+        .and_then(|presence| presence.board_id.clone())
+        .filter(|board_id| reducer_context.find_session(&session_id(board_id, reducer_context.sender)).is_some_and(|game_session| game_session.is_online));
+
+    let presence = PresenceDatabaseModel {
+        identity: reducer_context.sender,
+        is_online: true,
+        connected_date: reducer_context.timestamp,
+        last_seen: reducer_context.timestamp,
+        board_id,
+        disconnected_sessions: Vec::new(),
+    };
 
-    let mut games_iterator = board_id_played_by_index.filter((board_id.as_str(), reducer_context.sender));
+    if previous.is_some() {
+        reducer_context.db.presence().identity().update(presence);
+    } else {
+        reducer_context.db.presence().insert(presence);
+    }
+}
 
-    // Check if game already exists if not we should create a game, otherwise just set is_online to true
-    match games_iterator.nth(1) {
-        Some(game_session) => {
-            reducer_context.db.game_session().id().update(GameSessionDatabaseModel {
-                is_online: true, ..game_session
-            });
-        },
-        None => {
-            let initial_game_session_model = GameSessionDatabaseModel {
-                id: format!("{}-{}", board_id.clone(), reducer_context.sender.to_string()),
-                board_id: board_id.clone(),
-                started_date: reducer_context.timestamp,
-                played_by: reducer_context.sender,
-                finished: false,
-                is_online: true,
-                found_words: String::from(""),
-            };
+// Presence is kept per identity, not per connection: when one of several connections of the same identity drops,
+// all of the identity's sessions are marked offline until it connects again
+#[reducer(client_disconnected)]
+pub fn client_disconnected(reducer_context: &ReducerContext) {
+    let disconnected: Vec<String> = disconnect_player(reducer_context, reducer_context.sender);
 
-            reducer_context.db.game_session().insert(initial_game_session_model);
-        }
+    if let Some(presence) = reducer_context.db.presence().identity().find(reducer_context.sender) {
+        reducer_context.db.presence().identity().update(PresenceDatabaseModel {
+            is_online: false, last_seen: reducer_context.timestamp, disconnected_sessions: disconnected, ..presence
+        });
     }
+}
+
+fn set_presence_board(reducer_context: &ReducerContext, board_id: Option<String>) {
+    if let Some(presence) = reducer_context.db.presence().identity().find(reducer_context.sender) {
+        reducer_context.db.presence().identity().update(PresenceDatabaseModel {
+            last_seen: reducer_context.timestamp, board_id, ..presence
+        });
+    }
+}
+
+#[reducer]
+pub fn join_game(reducer_context: &ReducerContext, board_id: String) -> Result<(), String> {
+    let words_total = reducer_context.db.word().board_id().filter(board_id.as_str()).count() as u32;
+
+    join_session(reducer_context, &board_id, reducer_context.sender, reducer_context.timestamp, words_total);
+    set_presence_board(reducer_context, Some(board_id));
 
     return Ok(());
 }
 
+fn board_placements(reducer_context: &ReducerContext, board_id: &str) -> Result<Vec<Placement>, String> {
+    return reducer_context.db.word().board_id().filter(board_id)
+        .map(|word_model| Ok(Placement {
+            direction: word_model.direction.parse::<Direction>()?,
+            row: word_model.start_row as usize, col: word_model.start_col as usize,
+            word: word_model.word,
+            step: 0,
+        }))
+        .collect();
+}
+
+fn session_room(reducer_context: &ReducerContext, game_session: &GameSessionDatabaseModel) -> Option<RoomDatabaseModel> {
+    return game_session.room_id.as_ref()
+        .and_then(|room_id| reducer_context.db.game_room().id().find(room_id))
+        .filter(|room| room.ended_date.is_none());
+}
+
+// Sessions whose words are off limits for the player: everyone in the room, otherwise only the player's own
+fn claiming_sessions(reducer_context: &ReducerContext, game_session: &GameSessionDatabaseModel) -> Vec<String> {
+    let room_players: Vec<Identity> = session_room(reducer_context, game_session).map(|room| room.players).unwrap_or_default();
+
+    return claiming_players(&room_players, game_session.played_by).iter()
+        .map(|player| session_id(&game_session.board_id, *player))
+        .collect();
+}
+
+// Every word found on the board of the session by the players it shares a room with, as (found_by, word)
+fn room_found_words(reducer_context: &ReducerContext, game_session: &GameSessionDatabaseModel) -> Vec<(Identity, String)> {
+    return claiming_sessions(reducer_context, game_session).iter()
+        .flat_map(|session_id| reducer_context.db.found_word().session_id().filter(session_id.as_str()))
+        .map(|found_word| (found_word.found_by, found_word.word))
+        .collect();
+}
+
+fn session_found_words(reducer_context: &ReducerContext, game_session: &GameSessionDatabaseModel) -> Vec<String> {
+    return room_found_words(reducer_context, game_session).into_iter().map(|(_, word)| word).collect();
+}
+
+// Found and total words of the session, total comes from the word placements of its board.
+// Players of a co-op room share their progress
+fn session_progress(reducer_context: &ReducerContext, game_session: &GameSessionDatabaseModel) -> (u32, u32) {
+    let players: Vec<Identity> = match session_room(reducer_context, game_session) {
+        Some(room) => progress_players(room.mode.parse::<RoomMode>().unwrap_or(RoomMode::Versus), &room.players, game_session.played_by),
+        None => vec![game_session.played_by]
+    };
+    let found = words_found_by(&room_found_words(reducer_context, game_session), &players);
+    let total = reducer_context.db.word().board_id().filter(game_session.board_id.as_str()).count() as u32;
+
+    return (found, total);
+}
+
+fn found_word_model(game_session: &GameSessionDatabaseModel, board_id: &str, word: &str, start: CellPosition, end: CellPosition, found_at: Timestamp, found_by: Identity) -> FoundWordDatabaseModel {
+    return FoundWordDatabaseModel {
+        id: format!("{}-{}", game_session.id, word),
+        session_id: game_session.id.clone(),
+        board_id: board_id.to_string(),
+        word: word.to_string(),
+        found_at, start, end, found_by,
+    };
+}
+
 #[reducer]
-pub fn word_is_found(reducer_context: &ReducerContext, board_id: String, word: String) -> Result<(), String> {
-    let board_id_played_by_index: spacetimedb::RangedIndex<_, (String, Identity), _> = reducer_context.db.game_session().board_id_played_by();
+pub fn word_is_found(reducer_context: &ReducerContext, board_id: String, start: CellPosition, end: CellPosition) -> Result<(), String> {
+    let placements: Vec<Placement> = board_placements(reducer_context, &board_id)?;
+    let selection = Selection {
+        start_row: start.row as usize, start_col: start.col as usize,
+        end_row: end.row as usize, end_col: end.col as usize,
+    };
+    let placement: Placement = selection.find_placement(&placements).map_err(|error| error.to_string())?.clone();
+    let word: String = placement.word.clone();
 
-    let mut games_iterator = board_id_played_by_index.filter((board_id.as_str(), reducer_context.sender));
+    let game_session = find_player_session(reducer_context, &board_id, reducer_context.sender)?;
+    let word_id: String = format!("{}-{}", board_id, word);
 
-    match games_iterator.nth(1) {
-        Some(game_session) => {
-            let word_id: String = format!("{}-{}", board_id, word);
+    if game_session.finished {
+        return Err(format!("Game on board {} is already finished", board_id));
+    }
 
-            if let Some(word_model) = reducer_context.db.word().id().find(word_id) {
-                let mut words_to_concat: String = game_session.found_words;
+    if game_session.deadline.is_some_and(|deadline| reducer_context.timestamp > deadline) {
+        return Err(format!("Time is up for the game on board {}", board_id));
+    }
+
+    let word_model = match reducer_context.db.word().id().find(word_id) {
+        Some(word_model) => word_model,
+        None => return Err(format!("Could not find the word {} on board {}!", word, board_id))
+    };
 
-                words_to_concat.push_str(format!("{}|", word_model.word).as_str());
-                reducer_context.db.game_session().id().update(GameSessionDatabaseModel {
-                    found_words: words_to_concat, ..game_session
+    let found_word = found_word_model(&game_session, &board_id, &word_model.word, start, end, reducer_context.timestamp, reducer_context.sender);
+
+    let room_players: Vec<Identity> = session_room(reducer_context, &game_session).map(|room| room.players).unwrap_or_default();
+    if word_claimed(&word_model.word, &room_found_words(reducer_context, &game_session), &claiming_players(&room_players, game_session.played_by)) {
+        return Err(format!("Word {} was already found", word_model.word));
+    }
+
+    let elapsed = reducer_context.timestamp.duration_since(game_session.started_date).unwrap_or_default();
+    let hint = game_session.hints.iter()
+        .filter(|hint| hint.word == word_model.word)
+        .filter_map(|hint| HintLevel::from_value(hint.level))
+        .max();
+    let points = word_points(&word_model.word, placement.direction, elapsed, hint);
+
+    reducer_context.db.found_word().insert(found_word);
+
+    let (words_found, words_total) = session_progress(reducer_context, &game_session);
+    let sharing_sessions: Vec<String> = claiming_sessions(reducer_context, &game_session);
+    let cooperative = session_room(reducer_context, &game_session)
+        .is_some_and(|room| room.mode.parse::<RoomMode>() == Ok(RoomMode::Cooperative));
+
+    reducer_context.update_session(GameSessionDatabaseModel {
+        score: game_session.score + points, words_found, words_total, ..game_session
+    });
+
+    // the rest of a co-op room moves forward together with the player
+    if cooperative {
+        for session_id in sharing_sessions {
+            if let Some(room_session) = reducer_context.find_session(&session_id).filter(|room_session| room_session.played_by != reducer_context.sender) {
+                reducer_context.update_session(GameSessionDatabaseModel {
+                    words_found, ..room_session
                 });
-            } else {
-                return Err(format!("Could not find the word {} on board {}!", word, board_id))
             }
-        },
-        None => {
-            return Err(
-                format!("Your session might have changes! Game session was not started for this Identity on board: {}", board_id.clone())
-            )
         }
     }
 
     return Ok(());
 }
 
+// Moves words of sessions created before the found_word table into it. The old column only kept the
+// words, so they are recorded at the placement cells, found by the session's player when it started
+#[reducer]
+pub fn migrate_found_words(reducer_context: &ReducerContext) -> Result<(), String> {
+    let game_sessions: Vec<GameSessionDatabaseModel> = reducer_context.db.game_session().iter()
+        .filter(|game_session| !game_session.found_words.is_empty())
+        .collect();
+
+    for game_session in game_sessions {
+        let placements: Vec<Placement> = board_placements(reducer_context, &game_session.board_id)?;
+
+        for word in game_session.found_words.split('|').filter(|word| !word.is_empty()) {
+            let placement = match placements.iter().find(|placement| placement.word == word) {
+                Some(placement) => placement,
+                None => continue
+            };
+            let (end_row, end_col) = placement.cells().last().copied().unwrap_or((placement.row, placement.col));
+
+            let found_word = found_word_model(
+                &game_session, &game_session.board_id, &placement.word,
+                CellPosition { row: placement.row as u8, col: placement.col as u8 },
+                CellPosition { row: end_row as u8, col: end_col as u8 },
+                game_session.started_date, game_session.played_by
+            );
+
+            if reducer_context.db.found_word().id().find(&found_word.id).is_none() {
+                reducer_context.db.found_word().insert(found_word);
+            }
+        }
+
+        let (words_found, words_total) = session_progress(reducer_context, &game_session);
+        reducer_context.update_session(GameSessionDatabaseModel {
+            found_words: String::new(), words_found, words_total, ..game_session
+        });
+    }
+
+    return Ok(());
+}
+
+// The hint is written to the session, asking again on the same word reveals more of it
+#[reducer]
+pub fn request_hint(reducer_context: &ReducerContext, board_id: String) -> Result<(), String> {
+    let game_session = find_player_session(reducer_context, &board_id, reducer_context.sender)?;
+
+    let placements: Vec<Placement> = board_placements(reducer_context, &board_id)?;
+    let found_words: Vec<String> = session_found_words(reducer_context, &game_session);
+    let previous = game_session.hints.last()
+        .and_then(|hint| HintLevel::from_value(hint.level).map(|level| (hint.word.as_str(), level)));
+
+    let hint = match next_hint(&placements, &found_words, previous) {
+        Some(hint) => hint,
+        None => return Err(format!("All words on board {} were already found", board_id))
+    };
+
+    let mut hints: Vec<HintRecord> = game_session.hints.clone();
+    hints.push(HintRecord {
+        word: hint.word,
+        level: hint.level.value(),
+        start: CellPosition { row: hint.row as u8, col: hint.col as u8 },
+        direction: hint.direction.map(|direction| direction.to_string()),
+        end: hint.end.map(|(row, col)| CellPosition { row: row as u8, col: col as u8 }),
+        given_at: reducer_context.timestamp,
+    });
+
+    reducer_context.update_session(GameSessionDatabaseModel {
+        hints, ..game_session
+    });
+
+    return Ok(());
+}
+
 #[reducer]
 pub fn close_session(reducer_context: &ReducerContext, board_id: String) -> Result<(), String> {
-    let board_id_played_by_index: spacetimedb::RangedIndex<_, (String, Identity), _> = reducer_context.db.game_session().board_id_played_by();
+    close_player_session(reducer_context, &board_id, reducer_context.sender)?;
+    set_presence_board(reducer_context, None);
 
-    let mut games_iterator = board_id_played_by_index.filter((board_id.as_str(), reducer_context.sender));
+    return Ok(());
+}
 
-    match games_iterator.nth(1) {
-        Some(game_session) => {
-            reducer_context.db.game_session().id().update(GameSessionDatabaseModel {
-                is_online: false, ..game_session
-            });
+#[reducer]
+pub fn guess_solution(reducer_context: &ReducerContext, board_id: String, guess: String) -> Result<(), String> {
+    let game_session = find_player_session(reducer_context, &board_id, reducer_context.sender)?;
+
+    if game_session.finished {
+        return Err(format!("Game on board {} is already finished", board_id));
+    }
+
+    if game_session.deadline.is_some_and(|deadline| reducer_context.timestamp > deadline) {
+        return Err(format!("Time is up for the game on board {}", board_id));
+    }
+
+    if game_session.solution_attempts.len() >= MAX_SOLUTION_ATTEMPTS {
+        return Err(format!("All {} attempts to guess the solution were used", MAX_SOLUTION_ATTEMPTS));
+    }
+
+    let board = reducer_context.db.board().id().find(board_id.clone())
+        .ok_or(format!("Board {} does not exist", board_id))?;
+    let alphabet = Alphabet::for_language(&board.language).unwrap_or(Alphabet::english());
+    let correct = alphabet.same_message(&guess, &board.solution) || alphabet.same_message(&guess, &board.message);
+
+    let mut solution_attempts: Vec<SolutionAttempt> = game_session.solution_attempts.clone();
+    solution_attempts.push(SolutionAttempt { guess, correct, guessed_at: reducer_context.timestamp });
+
+    let game_session = GameSessionDatabaseModel { solution_attempts, ..game_session };
+    reducer_context.update_session(game_session.clone());
+
+    if correct {
+        finish_solved_game(reducer_context, &board, &game_session);
+    }
+
+    return Ok(());
+}
+
+// Deprecated, clients should finish the game with guess_solution. Kept so older clients keep working: it only
+// finishes the caller's own session, without the solution bonus and without touching anyone else's game
+#[reducer]
+pub fn finish_game(reducer_context: &ReducerContext, board_id: String) -> Result<(), String> {
+    if reducer_context.db.board().id().find(board_id.clone()).is_none() {
+        return Err(format!("Board {} does not exist", board_id));
+    }
+
+    return finish_player_session(reducer_context, &board_id, reducer_context.sender);
+}
+
+// Ends the game of the player who guessed the solution, who gets the bonus. In a room the game ends for all of
+// its players and the room is closed, everyone else on the board keeps playing
+fn finish_solved_game(reducer_context: &ReducerContext, board: &BoardDatabaseModel, game_session: &GameSessionDatabaseModel) {
+    let solved_by = game_session.played_by;
+    let room = session_room(reducer_context, game_session);
+    let room_players: Vec<Identity> = room.as_ref().map(|room| room.players.clone()).unwrap_or_default();
+
+    finish_sessions(reducer_context, &board.id, &finishing_players(&room_players, solved_by), |game_session| {
+        if game_session.played_by == solved_by {
+            solution_bonus(&board.solution, reducer_context.timestamp.duration_since(game_session.started_date).unwrap_or_default())
+        } else {
+            0
         }
-        None => {
-            return Err(
-                format!("Your session might have changes! Game session was not started for this Identity on board: {}", board_id.clone())
-            );
+    });
+
+    if let Some(room) = room {
+        end_room(reducer_context, room);
+    }
+}
+
+fn end_room(reducer_context: &ReducerContext, room: RoomDatabaseModel) {
+    let scores: Vec<(Identity, u32)> = room.players.iter()
+        .filter_map(|player| reducer_context.find_session(&session_id(&room.board_id, *player)))
+        .map(|game_session| (game_session.played_by, game_session.score))
+        .collect();
+
+    reducer_context.db.game_room().id().update(RoomDatabaseModel {
+        ended_date: Some(reducer_context.timestamp), winner: pick_winner(&scores), ..room
+    });
+}
+
+// Starts the clock on the player's session, the scheduled finish_timed_session ends it at the deadline
+#[reducer]
+pub fn start_timed_game(reducer_context: &ReducerContext, board_id: String, mode: String) -> Result<(), String> {
+    let mode: TimedMode = mode.parse()?;
+
+    if reducer_context.db.board().id().find(board_id.clone()).is_none() {
+        return Err(format!("Board {} does not exist", board_id));
+    }
+
+    let words_total = reducer_context.db.word().board_id().filter(board_id.as_str()).count() as u32;
+    join_session(reducer_context, &board_id, reducer_context.sender, reducer_context.timestamp, words_total);
+    set_presence_board(reducer_context, Some(board_id.clone()));
+
+    let deadline: Timestamp = reducer_context.timestamp + TimeDuration::from_micros(mode.duration().as_micros() as i64);
+    start_timed_session(reducer_context, &board_id, reducer_context.sender, reducer_context.timestamp, deadline)?;
+
+    reducer_context.db.session_deadline().insert(SessionDeadlineDatabaseModel {
+        scheduled_id: 0,
+        scheduled_at: deadline.into(),
+        board_id,
+        played_by: reducer_context.sender,
+    });
+
+    return Ok(());
+}
+
+#[reducer]
+pub fn finish_timed_session(reducer_context: &ReducerContext, session_deadline: SessionDeadlineDatabaseModel) -> Result<(), String> {
+    // only the scheduler may end a game early
+    if reducer_context.sender != reducer_context.identity() {
+        return Err("Timed games are finished only by their schedule".to_string());
+    }
+
+    let game_session = find_player_session(reducer_context, &session_deadline.board_id, session_deadline.played_by)?;
+    let room = session_room(reducer_context, &game_session);
+
+    if !finish_expired_session(reducer_context, &session_deadline.board_id, session_deadline.played_by, reducer_context.timestamp)? {
+        return Ok(());
+    }
+
+    // a room ends once the clock ran out for all of its players
+    if let Some(room) = room {
+        let all_finished = room.players.iter()
+            .filter_map(|player| reducer_context.find_session(&session_id(&room.board_id, *player)))
+            .all(|room_session| room_session.finished);
+
+        if all_finished {
+            end_room(reducer_context, room);
         }
     }
 
     return Ok(());
 }
 
+// Joins the room's board in a session tied to the room
+fn enter_room(reducer_context: &ReducerContext, room: &RoomDatabaseModel) -> Result<(), String> {
+    let words_total = reducer_context.db.word().board_id().filter(room.board_id.as_str()).count() as u32;
+    join_session(reducer_context, &room.board_id, reducer_context.sender, reducer_context.timestamp, words_total);
+
+    let game_session = find_player_session(reducer_context, &room.board_id, reducer_context.sender)?;
+    reducer_context.update_session(GameSessionDatabaseModel {
+        room_id: Some(room.id.clone()), ..game_session
+    });
+    set_presence_board(reducer_context, Some(room.board_id.clone()));
+
+    return Ok(());
+}
+
 #[reducer]
-pub fn finish_game(reducer_context: &ReducerContext, board_id: String) -> Result<(), String> {
-    let board_id_played_by_index: spacetimedb::RangedIndex<_, (String, Identity), _> = reducer_context.db.game_session().board_id_played_by();
+pub fn create_room(reducer_context: &ReducerContext, board_id: String, mode: String) -> Result<(), String> {
+    let mode: RoomMode = mode.parse()?;
 
-    let games_iterator = board_id_played_by_index.filter(board_id.as_str());
+    if reducer_context.db.board().id().find(board_id.clone()).is_none() {
+        return Err(format!("Board {} does not exist", board_id));
+    }
 
-    // if anyone finishes the game we should stop all game
-    for game_session in games_iterator {
-        reducer_context.db.game_session().id().update(GameSessionDatabaseModel {
-            finished: true, ..game_session
+    let mut number_rng = reducer_context.rng();
+    let room = RoomDatabaseModel {
+        id: id!(10, &mut number_rng),
+        board_id,
+        mode: mode.name().to_string(),
+        created_by: reducer_context.sender,
+        players: vec![reducer_context.sender],
+        started_date: reducer_context.timestamp,
+        ended_date: None,
+        winner: None,
+    };
+
+    enter_room(reducer_context, &room)?;
+    reducer_context.db.game_room().insert(room);
+
+    return Ok(());
+}
+
+#[reducer]
+pub fn join_room(reducer_context: &ReducerContext, room_id: String) -> Result<(), String> {
+    let room = reducer_context.db.game_room().id().find(room_id.clone())
+        .ok_or(format!("Room {} does not exist", room_id))?;
+
+    if room.ended_date.is_some() {
+        return Err(format!("Room {} has already ended", room_id));
+    }
+
+    enter_room(reducer_context, &room)?;
+
+    if !room.players.contains(&reducer_context.sender) {
+        let mut players: Vec<Identity> = room.players.clone();
+        players.push(reducer_context.sender);
+
+        reducer_context.db.game_room().id().update(RoomDatabaseModel {
+            players, ..room
         });
     }
 
     return Ok(());
 }
 
+// The last player to leave ends the room
+#[reducer]
+pub fn leave_room(reducer_context: &ReducerContext, room_id: String) -> Result<(), String> {
+    let room = reducer_context.db.game_room().id().find(room_id.clone())
+        .ok_or(format!("Room {} does not exist", room_id))?;
+
+    if !room.players.contains(&reducer_context.sender) {
+        return Err(format!("You are not a player in room {}", room_id));
+    }
+
+    let game_session = find_player_session(reducer_context, &room.board_id, reducer_context.sender)?;
+    reducer_context.update_session(GameSessionDatabaseModel {
+        room_id: None, ..game_session
+    });
+    close_player_session(reducer_context, &room.board_id, reducer_context.sender)?;
+    set_presence_board(reducer_context, None);
+
+    match remaining_players(&room.players, reducer_context.sender) {
+        None if room.ended_date.is_none() => end_room(reducer_context, room),
+        remaining => {
+            reducer_context.db.game_room().id().update(RoomDatabaseModel {
+                players: remaining.unwrap_or_default(), ..room
+            });
+        }
+    }
+
+    return Ok(());
+}
+
 #[reducer]
-pub fn save_word(reducer_context: &ReducerContext, word: String) -> Result<(), String> {
-    let dictionary_word = DictionaryDatabaseModel {
-        word: word, language: "en".to_string(), category: None
+pub fn save_word(reducer_context: &ReducerContext, word: String, language: String, category: Option<String>) -> Result<(), String> {
+    let word_exists = reducer_context.db.dictionary_word().language().filter(language.as_str())
+        .any(|w| w.word == word && w.category == category);
+
+    if word_exists {
+        return Err(format!("Word {} is already in {} dictionary", word, language))
+    }
+
+    let dictionary_word = DictionaryWordDatabaseModel {
+        id: 0, word: word, language: language, category: category
     };
-    reducer_context.db.dictionary().insert(dictionary_word);
+    reducer_context.db.dictionary_word().insert(dictionary_word);
+
+    return Ok(());
+}
+
+// Moves words saved before the dictionary_word table into it, words already there are not added twice
+#[reducer]
+pub fn migrate_dictionary(reducer_context: &ReducerContext) -> Result<(), String> {
+    let legacy_words: Vec<DictionaryDatabaseModel> = reducer_context.db.dictionary().iter().collect();
+
+    for legacy_word in legacy_words {
+        let word_exists = reducer_context.db.dictionary_word().language().filter(legacy_word.language.as_str())
+            .any(|w| w.word == legacy_word.word && w.category == legacy_word.category);
+
+        if !word_exists {
+            reducer_context.db.dictionary_word().insert(DictionaryWordDatabaseModel {
+                id: 0, word: legacy_word.word.clone(), language: legacy_word.language.clone(), category: legacy_word.category.clone()
+            });
+        }
+
+        reducer_context.db.dictionary().word().delete(&legacy_word.word);
+    }
 
     return Ok(());
 }
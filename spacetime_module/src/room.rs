@@ -0,0 +1,166 @@
+use std::str::FromStr;
+use spacetimedb::Identity;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoomMode {
+    // players share found words and progress
+    Cooperative,
+    // every word counts only for the player who claims it first
+    Versus,
+}
+
+impl RoomMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RoomMode::Cooperative => "coop",
+            RoomMode::Versus => "versus",
+        }
+    }
+}
+
+impl FromStr for RoomMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "coop" | "co-op" | "cooperative" => Ok(RoomMode::Cooperative),
+            "versus" | "vs" | "competitive" => Ok(RoomMode::Versus),
+            _ => Err(format!("Room mode {} is not supported", s))
+        }
+    }
+}
+
+// Players whose found words the player cannot find again: the whole room, because co-op players share their words
+// and in versus the first player to find a word claims it. Outside of a room only the player's own words count
+pub fn claiming_players(players: &[Identity], player: Identity) -> Vec<Identity> {
+    let mut claiming: Vec<Identity> = players.to_vec();
+    if !claiming.contains(&player) {
+        claiming.push(player);
+    }
+
+    return claiming;
+}
+
+// Players whose game ends when the player guesses the solution: the player's room, otherwise only the player.
+// Other rooms and players on the same board keep playing
+pub fn finishing_players(players: &[Identity], solved_by: Identity) -> Vec<Identity> {
+    return claiming_players(players, solved_by);
+}
+
+// Players whose found words make up the player's progress, a co-op room moves forward together
+pub fn progress_players(mode: RoomMode, players: &[Identity], player: Identity) -> Vec<Identity> {
+    match mode {
+        RoomMode::Cooperative => claiming_players(players, player),
+        RoomMode::Versus => vec![player],
+    }
+}
+
+// Found words are given as (found_by, word)
+pub fn word_claimed(word: &str, found_words: &[(Identity, String)], claiming: &[Identity]) -> bool {
+    return found_words.iter().any(|(found_by, found_word)| found_word == word && claiming.contains(found_by));
+}
+
+// Distinct words found by any of the players, found words are given as (found_by, word)
+pub fn words_found_by(found_words: &[(Identity, String)], players: &[Identity]) -> u32 {
+    let mut words: Vec<&String> = found_words.iter()
+        .filter(|(found_by, _)| players.contains(found_by))
+        .map(|(_, word)| word)
+        .collect();
+    words.sort();
+    words.dedup();
+
+    return words.len() as u32;
+}
+
+// Players left once the player leaves, None when nobody is left and the room ends
+pub fn remaining_players(players: &[Identity], leaving: Identity) -> Option<Vec<Identity>> {
+    let remaining: Vec<Identity> = players.iter().filter(|player| **player != leaving).cloned().collect();
+
+    return if remaining.is_empty() { None } else { Some(remaining) };
+}
+
+// Player with the highest score, nobody wins a tie or an empty room
+pub fn pick_winner(scores: &[(Identity, u32)]) -> Option<Identity> {
+    let best = scores.iter().map(|(_, score)| *score).max()?;
+    let mut leaders = scores.iter().filter(|(_, score)| *score == best);
+
+    return match (leaders.next(), leaders.next()) {
+        (Some((player, _)), None) => Some(*player),
+        _ => None
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(byte: u8) -> Identity {
+        Identity::from_byte_array([byte; 32])
+    }
+
+    #[test]
+    fn modes_parse_by_name() {
+        assert_eq!("coop".parse::<RoomMode>(), Ok(RoomMode::Cooperative));
+        assert_eq!("Versus".parse::<RoomMode>(), Ok(RoomMode::Versus));
+        assert_eq!(RoomMode::Versus.name().parse::<RoomMode>(), Ok(RoomMode::Versus));
+        assert!("solo".parse::<RoomMode>().is_err());
+    }
+
+    fn found(words: &[(u8, &str)]) -> Vec<(Identity, String)> {
+        words.iter().map(|(byte, word)| (player(*byte), word.to_string())).collect()
+    }
+
+    #[test]
+    fn versus_word_found_by_one_player_is_rejected_for_the_other() {
+        let players = vec![player(1), player(2)];
+        let found_words = found(&[(1, "CAT")]);
+
+        assert!(word_claimed("CAT", &found_words, &claiming_players(&players, player(2))));
+        assert!(!word_claimed("DOG", &found_words, &claiming_players(&players, player(2))));
+        // the same word is free outside of the room
+        assert!(!word_claimed("CAT", &found_words, &claiming_players(&Vec::new(), player(3))));
+    }
+
+    #[test]
+    fn versus_players_progress_on_their_own() {
+        let players = vec![player(1), player(2)];
+        let found_words = found(&[(1, "CAT"), (1, "DOG"), (2, "EMU")]);
+
+        assert_eq!(words_found_by(&found_words, &progress_players(RoomMode::Versus, &players, player(1))), 2);
+        assert_eq!(words_found_by(&found_words, &progress_players(RoomMode::Versus, &players, player(2))), 1);
+    }
+
+    #[test]
+    fn coop_players_share_progress() {
+        let players = vec![player(1), player(2)];
+        let found_words = found(&[(1, "CAT"), (1, "DOG"), (2, "EMU")]);
+
+        assert_eq!(words_found_by(&found_words, &progress_players(RoomMode::Cooperative, &players, player(1))), 3);
+        assert_eq!(words_found_by(&found_words, &progress_players(RoomMode::Cooperative, &players, player(2))), 3);
+        assert!(word_claimed("CAT", &found_words, &claiming_players(&players, player(2))));
+    }
+
+    #[test]
+    fn solution_ends_the_room_or_only_the_solo_player() {
+        let players = vec![player(1), player(2)];
+
+        assert_eq!(finishing_players(&players, player(2)), vec![player(1), player(2)]);
+        assert_eq!(finishing_players(&Vec::new(), player(3)), vec![player(3)]);
+    }
+
+    #[test]
+    fn last_player_to_leave_ends_the_room() {
+        let players = vec![player(1), player(2)];
+
+        let remaining = remaining_players(&players, player(1));
+        assert_eq!(remaining, Some(vec![player(2)]));
+        assert_eq!(remaining_players(&remaining.unwrap(), player(2)), None);
+    }
+
+    #[test]
+    fn highest_score_wins_unless_tied() {
+        assert_eq!(pick_winner(&[(player(1), 30), (player(2), 50)]), Some(player(2)));
+        assert_eq!(pick_winner(&[(player(1), 50), (player(2), 50)]), None);
+        assert_eq!(pick_winner(&Vec::new()), None);
+    }
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+use types::{Direction, HintLevel};
+
+const POINTS_PER_LETTER: u32 = 10;
+const SOLUTION_POINTS_PER_LETTER: u32 = 20;
+// words found within this time get the whole time bonus, it then fades out until TIME_BONUS_END
+const TIME_BONUS_START: Duration = Duration::from_secs(2 * 60);
+const TIME_BONUS_END: Duration = Duration::from_secs(10 * 60);
+const MAX_TIME_BONUS_PERCENT: u32 = 50;
+
+// Words read backwards or diagonally are harder to spot, so they are worth more
+fn direction_percent(direction: Direction) -> u32 {
+    match direction {
+        Direction::East => 100,
+        Direction::South => 110,
+        Direction::SouthEast => 120,
+        Direction::West | Direction::North => 130,
+        Direction::NorthEast | Direction::SouthWest => 140,
+        Direction::NorthWest => 150,
+    }
+}
+
+// Share of the points left after the strongest hint given for the word
+fn hint_percent(hint: Option<HintLevel>) -> u32 {
+    match hint {
+        None => 100,
+        Some(HintLevel::FirstLetter) => 75,
+        Some(HintLevel::Direction) => 50,
+        Some(HintLevel::Placement) => 25,
+    }
+}
+
+fn time_bonus_percent(elapsed: Duration) -> u32 {
+    if elapsed <= TIME_BONUS_START {
+        return MAX_TIME_BONUS_PERCENT;
+    }
+
+    if elapsed >= TIME_BONUS_END {
+        return 0;
+    }
+
+    let remaining = (TIME_BONUS_END - elapsed).as_secs() as u32;
+    let window = (TIME_BONUS_END - TIME_BONUS_START).as_secs() as u32;
+
+    return MAX_TIME_BONUS_PERCENT * remaining / window;
+}
+
+pub fn word_points(word: &str, direction: Direction, elapsed: Duration, hint: Option<HintLevel>) -> u32 {
+    let base = word.chars().count() as u32 * POINTS_PER_LETTER;
+    let with_direction = base * direction_percent(direction) / 100;
+    let with_time = with_direction * (100 + time_bonus_percent(elapsed)) / 100;
+
+    return with_time * hint_percent(hint) / 100;
+}
+
+pub fn solution_bonus(solution: &str, elapsed: Duration) -> u32 {
+    let base = solution.chars().filter(|letter| letter.is_alphanumeric()).count() as u32 * SOLUTION_POINTS_PER_LETTER;
+
+    return base * (100 + time_bonus_percent(elapsed)) / 100;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // past the time bonus, so only the multiplier under test changes the points
+    const LATE: Duration = TIME_BONUS_END;
+
+    #[test]
+    fn harder_directions_are_worth_more() {
+        let points: Vec<u32> = [Direction::East, Direction::South, Direction::SouthEast, Direction::West, Direction::NorthEast, Direction::NorthWest]
+            .iter()
+            .map(|direction| word_points("CAT", *direction, LATE, None))
+            .collect();
+
+        assert_eq!(points, vec![30, 33, 36, 39, 42, 45]);
+        assert_eq!(word_points("CAT", Direction::North, LATE, None), word_points("CAT", Direction::West, LATE, None));
+        assert_eq!(word_points("CAT", Direction::SouthWest, LATE, None), word_points("CAT", Direction::NorthEast, LATE, None));
+    }
+
+    #[test]
+    fn time_bonus_fades_out() {
+        let points = |elapsed: Duration| word_points("CAT", Direction::East, elapsed, None);
+
+        assert_eq!(points(Duration::ZERO), 45);
+        assert_eq!(points(TIME_BONUS_START), 45);
+        assert_eq!(points(Duration::from_secs(6 * 60)), 37);
+        assert_eq!(points(TIME_BONUS_END), 30);
+        assert_eq!(points(Duration::from_secs(60 * 60)), 30);
+    }
+
+    #[test]
+    fn stronger_hints_leave_fewer_points() {
+        let points = |hint: Option<HintLevel>| word_points("CAT", Direction::East, LATE, hint);
+
+        assert_eq!(points(None), 30);
+        assert_eq!(points(Some(HintLevel::FirstLetter)), 22);
+        assert_eq!(points(Some(HintLevel::Direction)), 15);
+        assert_eq!(points(Some(HintLevel::Placement)), 7);
+    }
+
+    #[test]
+    fn hint_penalty_never_goes_below_zero() {
+        assert_eq!(word_points("", Direction::East, LATE, Some(HintLevel::Placement)), 0);
+        assert_eq!(word_points("A", Direction::East, LATE, Some(HintLevel::Placement)), 2);
+        assert_eq!(word_points("A", Direction::East, Duration::MAX, Some(HintLevel::Placement)), 2);
+    }
+
+    #[test]
+    fn solution_bonus_counts_only_letters() {
+        assert_eq!(solution_bonus("Hello, world", LATE), 200);
+        assert_eq!(solution_bonus("Hello, world", Duration::ZERO), 300);
+    }
+}
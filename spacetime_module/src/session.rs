@@ -0,0 +1,412 @@
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+use crate::{game_session, GameSessionDatabaseModel};
+
+// Access to the game_session table, kept behind a trait so the session flows can be tested without a host
+pub trait SessionStore {
+    fn find_session(&self, id: &str) -> Option<GameSessionDatabaseModel>;
+    fn insert_session(&self, game_session: GameSessionDatabaseModel);
+    fn update_session(&self, game_session: GameSessionDatabaseModel);
+    fn board_sessions(&self, board_id: &str) -> Vec<GameSessionDatabaseModel>;
+    fn player_sessions(&self, played_by: Identity) -> Vec<GameSessionDatabaseModel>;
+}
+
+impl SessionStore for ReducerContext {
+    fn find_session(&self, id: &str) -> Option<GameSessionDatabaseModel> {
+        return self.db.game_session().id().find(id.to_string());
+    }
+
+    fn insert_session(&self, game_session: GameSessionDatabaseModel) {
+        self.db.game_session().insert(game_session);
+    }
+
+    fn update_session(&self, game_session: GameSessionDatabaseModel) {
+        self.db.game_session().id().update(game_session);
+    }
+
+    fn board_sessions(&self, board_id: &str) -> Vec<GameSessionDatabaseModel> {
+        let board_id_played_by_index: spacetimedb::RangedIndex<_, (String, Identity), _> = self.db.game_session().board_id_played_by();
+
+        return board_id_played_by_index.filter(board_id).collect();
+    }
+
+    fn player_sessions(&self, played_by: Identity) -> Vec<GameSessionDatabaseModel> {
+        return self.db.game_session().played_by().filter(played_by).collect();
+    }
+}
+
+// A player has exactly one session per board, so the id is derived from both
+pub fn session_id(board_id: &String, played_by: Identity) -> String {
+    return format!("{}-{}", board_id, played_by.to_string());
+}
+
+pub fn find_player_session<S: SessionStore + ?Sized>(store: &S, board_id: &String, played_by: Identity) -> Result<GameSessionDatabaseModel, String> {
+    return store.find_session(&session_id(board_id, played_by))
+        .ok_or(format!("Game session was not started for this Identity on board: {}", board_id));
+}
+
+// Creates the session on the first join, later joins only bring the player back online
+pub fn join_session<S: SessionStore + ?Sized>(store: &S, board_id: &String, played_by: Identity, now: Timestamp, words_total: u32) {
+    match store.find_session(&session_id(board_id, played_by)) {
+        Some(game_session) => store.update_session(GameSessionDatabaseModel {
+            is_online: true, ..game_session
+        }),
+        None => store.insert_session(GameSessionDatabaseModel {
+            id: session_id(board_id, played_by),
+            board_id: board_id.clone(),
+            started_date: now,
+            played_by,
+            finished: false,
+            is_online: true,
+            found_words: String::from(""),
+            hints: Vec::new(),
+            score: 0,
+            solution_attempts: Vec::new(),
+            words_found: 0,
+            words_total,
+            room_id: None,
+            deadline: None,
+        })
+    }
+}
+
+pub fn close_player_session<S: SessionStore + ?Sized>(store: &S, board_id: &String, played_by: Identity) -> Result<(), String> {
+    let game_session = find_player_session(store, board_id, played_by)?;
+
+    store.update_session(GameSessionDatabaseModel {
+        is_online: false, ..game_session
+    });
+
+    return Ok(());
+}
+
+// Takes every open session of the player offline and returns their ids, so reconnecting brings back only these.
+// Sessions the player closed or finished stay as they were left
+pub fn disconnect_player<S: SessionStore + ?Sized>(store: &S, played_by: Identity) -> Vec<String> {
+    let mut disconnected: Vec<String> = Vec::new();
+
+    for game_session in store.player_sessions(played_by) {
+        if game_session.finished || !game_session.is_online {
+            continue;
+        }
+
+        disconnected.push(game_session.id.clone());
+        store.update_session(GameSessionDatabaseModel {
+            is_online: false, ..game_session
+        });
+    }
+
+    return disconnected;
+}
+
+// Brings back the sessions disconnect_player took offline, unless they were finished in the meantime
+pub fn reconnect_player<S: SessionStore + ?Sized>(store: &S, disconnected: &[String]) {
+    for game_session in disconnected.iter().filter_map(|id| store.find_session(id)) {
+        if game_session.finished || game_session.is_online {
+            continue;
+        }
+
+        store.update_session(GameSessionDatabaseModel {
+            is_online: true, ..game_session
+        });
+    }
+}
+
+pub fn finish_player_session<S: SessionStore + ?Sized>(store: &S, board_id: &String, played_by: Identity) -> Result<(), String> {
+    let game_session = find_player_session(store, board_id, played_by)?;
+
+    store.update_session(GameSessionDatabaseModel {
+        finished: true, ..game_session
+    });
+
+    return Ok(());
+}
+
+// Starts the clock on a fresh session, a session with any progress or an earlier clock cannot be timed anymore.
+// The time bonus of the score counts from the start of the clock
+pub fn start_timed_session<S: SessionStore + ?Sized>(store: &S, board_id: &String, played_by: Identity, now: Timestamp, deadline: Timestamp) -> Result<(), String> {
+    let game_session = find_player_session(store, board_id, played_by)?;
+
+    if game_session.finished || game_session.deadline.is_some() {
+        return Err(format!("Timed game on board {} was already played", board_id));
+    }
+
+    if game_session.words_found > 0 || game_session.score > 0 || !game_session.hints.is_empty() {
+        return Err(format!("Game on board {} was already started without a clock", board_id));
+    }
+
+    store.update_session(GameSessionDatabaseModel {
+        started_date: now, deadline: Some(deadline), ..game_session
+    });
+
+    return Ok(());
+}
+
+// Finishes the timed session once its deadline has passed, true when it was finished by this call
+pub fn finish_expired_session<S: SessionStore + ?Sized>(store: &S, board_id: &String, played_by: Identity, now: Timestamp) -> Result<bool, String> {
+    let game_session = find_player_session(store, board_id, played_by)?;
+
+    if game_session.finished || game_session.deadline.is_none_or(|deadline| now < deadline) {
+        return Ok(false);
+    }
+
+    finish_player_session(store, board_id, played_by)?;
+
+    return Ok(true);
+}
+
+// Ends the unfinished sessions of the players on the board, bonus decides the extra points of each of them.
+// Everyone else on the board keeps playing
+pub fn finish_sessions<S: SessionStore + ?Sized>(store: &S, board_id: &str, players: &[Identity], bonus: impl Fn(&GameSessionDatabaseModel) -> u32) {
+    for game_session in store.board_sessions(board_id) {
+        if game_session.finished || !players.contains(&game_session.played_by) {
+            continue;
+        }
+
+        let points = bonus(&game_session);
+        store.update_session(GameSessionDatabaseModel {
+            finished: true, score: game_session.score + points, ..game_session
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use super::*;
+
+    // In memory stand-in for the game_session table, keyed by the primary key like the real one
+    #[derive(Default)]
+    struct MemorySessions {
+        rows: RefCell<BTreeMap<String, GameSessionDatabaseModel>>,
+    }
+
+    impl SessionStore for MemorySessions {
+        fn find_session(&self, id: &str) -> Option<GameSessionDatabaseModel> {
+            return self.rows.borrow().get(id).cloned();
+        }
+
+        fn insert_session(&self, game_session: GameSessionDatabaseModel) {
+            let previous = self.rows.borrow_mut().insert(game_session.id.clone(), game_session);
+            assert!(previous.is_none(), "duplicate session id");
+        }
+
+        fn update_session(&self, game_session: GameSessionDatabaseModel) {
+            let previous = self.rows.borrow_mut().insert(game_session.id.clone(), game_session);
+            assert!(previous.is_some(), "updated session does not exist");
+        }
+
+        fn board_sessions(&self, board_id: &str) -> Vec<GameSessionDatabaseModel> {
+            return self.rows.borrow().values().filter(|game_session| game_session.board_id == board_id).cloned().collect();
+        }
+
+        fn player_sessions(&self, played_by: Identity) -> Vec<GameSessionDatabaseModel> {
+            return self.rows.borrow().values().filter(|game_session| game_session.played_by == played_by).cloned().collect();
+        }
+    }
+
+    fn player(byte: u8) -> Identity {
+        Identity::from_byte_array([byte; 32])
+    }
+
+    fn board() -> String {
+        "board".to_string()
+    }
+
+    fn now() -> Timestamp {
+        Timestamp::from_micros_since_unix_epoch(1_700_000_000_000_000)
+    }
+
+    fn minutes_later(minutes: i64) -> Timestamp {
+        Timestamp::from_micros_since_unix_epoch(1_700_000_000_000_000 + minutes * 60_000_000)
+    }
+
+    #[test]
+    fn join_creates_one_session() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+
+        let game_session = find_player_session(&store, &board(), player(1)).unwrap();
+        assert_eq!(game_session.played_by, player(1));
+        assert_eq!(game_session.words_total, 5);
+        assert!(game_session.is_online && !game_session.finished);
+    }
+
+    #[test]
+    fn rejoin_reuses_the_session() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+        store.update_session(GameSessionDatabaseModel {
+            score: 40, ..find_player_session(&store, &board(), player(1)).unwrap()
+        });
+        close_player_session(&store, &board(), player(1)).unwrap();
+
+        join_session(&store, &board(), player(1), now(), 5);
+
+        let game_session = find_player_session(&store, &board(), player(1)).unwrap();
+        assert_eq!(store.board_sessions(&board()).len(), 1);
+        assert!(game_session.is_online);
+        assert_eq!(game_session.score, 40);
+    }
+
+    #[test]
+    fn session_ids_are_unique_per_board_and_player() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+        join_session(&store, &board(), player(2), now(), 5);
+        join_session(&store, &"other".to_string(), player(1), now(), 5);
+
+        assert_ne!(session_id(&board(), player(1)), session_id(&board(), player(2)));
+        assert_ne!(session_id(&board(), player(1)), session_id(&"other".to_string(), player(1)));
+        assert_eq!(store.board_sessions(&board()).len(), 2);
+    }
+
+    #[test]
+    fn find_returns_the_only_session() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+
+        assert_eq!(find_player_session(&store, &board(), player(1)).unwrap().id, session_id(&board(), player(1)));
+        assert!(find_player_session(&store, &board(), player(2)).is_err());
+        assert!(find_player_session(&store, &"other".to_string(), player(1)).is_err());
+    }
+
+    #[test]
+    fn close_takes_the_player_offline() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+        join_session(&store, &board(), player(2), now(), 5);
+
+        close_player_session(&store, &board(), player(1)).unwrap();
+
+        assert!(!find_player_session(&store, &board(), player(1)).unwrap().is_online);
+        assert!(find_player_session(&store, &board(), player(2)).unwrap().is_online);
+        assert!(close_player_session(&store, &board(), player(3)).is_err());
+    }
+
+    #[test]
+    fn finish_ends_only_the_given_sessions_once() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+        join_session(&store, &board(), player(2), now(), 5);
+        join_session(&store, &board(), player(3), now(), 5);
+        join_session(&store, &"other".to_string(), player(1), now(), 5);
+
+        let bonus = |game_session: &GameSessionDatabaseModel| if game_session.played_by == player(1) { 100 } else { 0 };
+        finish_sessions(&store, &board(), &[player(1), player(2)], bonus);
+        finish_sessions(&store, &board(), &[player(1), player(2)], bonus);
+
+        let winner = find_player_session(&store, &board(), player(1)).unwrap();
+        let teammate = find_player_session(&store, &board(), player(2)).unwrap();
+        assert!(winner.finished && teammate.finished);
+        assert_eq!((winner.score, teammate.score), (100, 0));
+        assert!(!find_player_session(&store, &board(), player(3)).unwrap().finished);
+        assert!(!find_player_session(&store, &"other".to_string(), player(1)).unwrap().finished);
+    }
+
+    #[test]
+    fn connection_changes_follow_every_unfinished_session() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+        join_session(&store, &"other".to_string(), player(1), now(), 5);
+        join_session(&store, &board(), player(2), now(), 5);
+        finish_sessions(&store, "other", &[player(1)], |_| 0);
+
+        let disconnected = disconnect_player(&store, player(1));
+
+        assert_eq!(disconnected, vec![session_id(&board(), player(1))]);
+        assert!(!find_player_session(&store, &board(), player(1)).unwrap().is_online);
+        assert!(find_player_session(&store, &"other".to_string(), player(1)).unwrap().is_online);
+        assert!(find_player_session(&store, &board(), player(2)).unwrap().is_online);
+
+        reconnect_player(&store, &disconnected);
+
+        assert!(find_player_session(&store, &board(), player(1)).unwrap().is_online);
+    }
+
+    #[test]
+    fn reconnect_does_not_reopen_closed_sessions() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+        join_session(&store, &"other".to_string(), player(1), now(), 5);
+        close_player_session(&store, &"other".to_string(), player(1)).unwrap();
+
+        let disconnected = disconnect_player(&store, player(1));
+        reconnect_player(&store, &disconnected);
+
+        assert!(find_player_session(&store, &board(), player(1)).unwrap().is_online);
+        assert!(!find_player_session(&store, &"other".to_string(), player(1)).unwrap().is_online);
+    }
+
+    #[test]
+    fn reconnect_skips_sessions_finished_while_offline() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+
+        let disconnected = disconnect_player(&store, player(1));
+        finish_sessions(&store, &board(), &[player(1)], |_| 0);
+        reconnect_player(&store, &disconnected);
+
+        let game_session = find_player_session(&store, &board(), player(1)).unwrap();
+        assert!(game_session.finished && !game_session.is_online);
+    }
+
+    #[test]
+    fn finish_ends_only_the_players_session() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+        join_session(&store, &board(), player(2), now(), 5);
+
+        finish_player_session(&store, &board(), player(1)).unwrap();
+
+        assert!(find_player_session(&store, &board(), player(1)).unwrap().finished);
+        assert!(!find_player_session(&store, &board(), player(2)).unwrap().finished);
+        assert!(finish_player_session(&store, &board(), player(3)).is_err());
+    }
+
+    #[test]
+    fn timed_start_sets_the_clock_once() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+
+        start_timed_session(&store, &board(), player(1), minutes_later(1), minutes_later(4)).unwrap();
+
+        let game_session = find_player_session(&store, &board(), player(1)).unwrap();
+        assert_eq!((game_session.started_date, game_session.deadline), (minutes_later(1), Some(minutes_later(4))));
+        assert!(start_timed_session(&store, &board(), player(1), minutes_later(2), minutes_later(5)).is_err());
+    }
+
+    #[test]
+    fn timed_start_rejects_sessions_with_progress() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+        store.update_session(GameSessionDatabaseModel {
+            words_found: 2, score: 60, ..find_player_session(&store, &board(), player(1)).unwrap()
+        });
+
+        assert!(start_timed_session(&store, &board(), player(1), now(), minutes_later(3)).is_err());
+        assert_eq!(find_player_session(&store, &board(), player(1)).unwrap().deadline, None);
+    }
+
+    #[test]
+    fn timed_session_finishes_at_the_deadline() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+        start_timed_session(&store, &board(), player(1), now(), minutes_later(3)).unwrap();
+
+        assert_eq!(finish_expired_session(&store, &board(), player(1), minutes_later(2)), Ok(false));
+        assert!(!find_player_session(&store, &board(), player(1)).unwrap().finished);
+
+        assert_eq!(finish_expired_session(&store, &board(), player(1), minutes_later(3)), Ok(true));
+        assert!(find_player_session(&store, &board(), player(1)).unwrap().finished);
+        assert_eq!(finish_expired_session(&store, &board(), player(1), minutes_later(4)), Ok(false));
+    }
+
+    #[test]
+    fn untimed_session_never_expires() {
+        let store = MemorySessions::default();
+        join_session(&store, &board(), player(1), now(), 5);
+
+        assert_eq!(finish_expired_session(&store, &board(), player(1), minutes_later(60)), Ok(false));
+    }
+}
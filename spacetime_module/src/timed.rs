@@ -0,0 +1,41 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimedMode {
+    Blitz,
+    Rapid,
+}
+
+impl TimedMode {
+    // time the player has from the start until the session is finished for them
+    pub fn duration(&self) -> Duration {
+        match self {
+            TimedMode::Blitz => Duration::from_secs(3 * 60),
+            TimedMode::Rapid => Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+impl FromStr for TimedMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "blitz" => Ok(TimedMode::Blitz),
+            "rapid" => Ok(TimedMode::Rapid),
+            _ => Err(format!("Timed mode {} is not supported", s))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blitz_lasts_three_minutes() {
+        assert_eq!("Blitz".parse::<TimedMode>().map(|mode| mode.duration()), Ok(Duration::from_secs(180)));
+        assert!("endless".parse::<TimedMode>().is_err());
+    }
+}
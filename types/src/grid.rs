@@ -0,0 +1,192 @@
+use std::fmt::{Display, Formatter};
+use crate::{Cell, MAX_GRID_SIDE};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridError {
+    LengthMismatch { rows: usize, cols: usize, length: usize },
+    RaggedRow { row: usize, expected: usize, actual: usize },
+    InvalidSolutionCell { row: usize, col: usize },
+    TooLarge { rows: usize, cols: usize },
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::LengthMismatch { rows, cols, length } => write!(
+                f, "Grid of {} cells does not fit a {}x{} board", length, rows, cols
+            ),
+            GridError::RaggedRow { row, expected, actual } => write!(
+                f, "Row {} has {} cells instead of {}", row, actual, expected
+            ),
+            GridError::InvalidSolutionCell { row, col } => write!(
+                f, "Solution cell ({}, {}) does not hold a letter", row, col
+            ),
+            GridError::TooLarge { rows, cols } => write!(
+                f, "Grid of {}x{} is larger than {}x{}", rows, cols, MAX_GRID_SIDE, MAX_GRID_SIDE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+// Row major: the first cols characters are the first row, one character per cell
+pub fn to_grid_string(rows: usize, cols: usize, grid: &[Vec<Cell>]) -> Result<String, GridError> {
+    if grid.len() != rows {
+        return Err(GridError::LengthMismatch { rows, cols, length: grid.iter().map(|r| r.len()).sum() });
+    }
+
+    if let Some((row, cells)) = grid.iter().enumerate().find(|(_, cells)| cells.len() != cols) {
+        return Err(GridError::RaggedRow { row, expected: cols, actual: cells.len() });
+    }
+
+    return Ok(grid.iter().flatten().map(|cell| cell.to_char()).collect());
+}
+
+// Reverses to_grid_string, cells listed in solution_cells become solution letters
+pub fn from_grid_string(rows: usize, cols: usize, grid: &str, solution_cells: &[(usize, usize)]) -> Result<Vec<Vec<Cell>>, GridError> {
+    // dimensions come from stored or imported data, so they are checked before anything is allocated
+    if rows > MAX_GRID_SIDE || cols > MAX_GRID_SIDE {
+        return Err(GridError::TooLarge { rows, cols });
+    }
+
+    let chars: Vec<char> = grid.chars().collect();
+
+    if rows.checked_mul(cols) != Some(chars.len()) {
+        return Err(GridError::LengthMismatch { rows, cols, length: chars.len() });
+    }
+
+    let mut cells: Vec<Vec<Cell>> = (0..rows)
+        .map(|row| chars[row * cols..(row + 1) * cols].iter().map(|c| Cell::from_char(*c)).collect())
+        .collect();
+
+    for (row, col) in solution_cells {
+        match cells.get(*row).and_then(|r| r.get(*col)).cloned() {
+            Some(Cell::Letter(letter)) => cells[*row][*col] = Cell::Solution(letter),
+            _ => return Err(GridError::InvalidSolutionCell { row: *row, col: *col })
+        }
+    }
+
+    return Ok(cells);
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+    use super::*;
+
+    const LETTERS: [char; 8] = ['A', 'Z', 'Á', 'Ž', 'ß', 'Ö', 'e', 'ň'];
+
+    #[derive(Clone, Debug)]
+    struct RandomGrid {
+        rows: usize,
+        cols: usize,
+        cells: Vec<Vec<Cell>>,
+    }
+
+    impl RandomGrid {
+        fn solution_cells(&self) -> Vec<(usize, usize)> {
+            let mut solution_cells: Vec<(usize, usize)> = Vec::new();
+
+            for (row, cells) in self.cells.iter().enumerate() {
+                for (col, cell) in cells.iter().enumerate() {
+                    if let Cell::Solution(_) = cell {
+                        solution_cells.push((row, col));
+                    }
+                }
+            }
+
+            return solution_cells;
+        }
+    }
+
+    impl Arbitrary for RandomGrid {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let rows = usize::arbitrary(g) % 15 + 1;
+            let cols = usize::arbitrary(g) % 15 + 1;
+
+            let cells = (0..rows).map(|_| (0..cols).map(|_| {
+                let letter = *g.choose(&LETTERS).unwrap();
+
+                match u8::arbitrary(g) % 4 {
+                    0 => Cell::Empty,
+                    1 => Cell::Blocked,
+                    2 => Cell::Letter(letter),
+                    _ => Cell::Solution(letter),
+                }
+            }).collect()).collect();
+
+            RandomGrid { rows, cols, cells }
+        }
+    }
+
+    #[test]
+    fn random_grids_round_trip() {
+        fn round_trip(grid: RandomGrid) -> bool {
+            let encoded = to_grid_string(grid.rows, grid.cols, &grid.cells).unwrap();
+
+            from_grid_string(grid.rows, grid.cols, &encoded, &grid.solution_cells()) == Ok(grid.cells)
+        }
+
+        quickcheck(round_trip as fn(RandomGrid) -> bool);
+    }
+
+    #[test]
+    fn encoded_length_matches_dimensions() {
+        fn length(grid: RandomGrid) -> bool {
+            to_grid_string(grid.rows, grid.cols, &grid.cells).unwrap().chars().count() == grid.rows * grid.cols
+        }
+
+        quickcheck(length as fn(RandomGrid) -> bool);
+    }
+
+    #[test]
+    fn non_square_grid_keeps_rows() {
+        let grid = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuv";
+        let cells = from_grid_string(6, 8, grid, &Vec::new()).unwrap();
+
+        assert_eq!(cells.len(), 6);
+        assert!(cells.iter().all(|row| row.len() == 8));
+        assert_eq!(cells[1][0], Cell::Letter('I'));
+        assert_eq!(to_grid_string(6, 8, &cells).unwrap(), grid);
+    }
+
+    #[test]
+    fn wrong_length_is_rejected() {
+        assert_eq!(
+            from_grid_string(3, 4, "ABCDEFGHIJK", &Vec::new()),
+            Err(GridError::LengthMismatch { rows: 3, cols: 4, length: 11 })
+        );
+    }
+
+    #[test]
+    fn oversized_grid_is_rejected() {
+        assert_eq!(
+            from_grid_string(MAX_GRID_SIDE + 1, 2, "AB", &Vec::new()),
+            Err(GridError::TooLarge { rows: MAX_GRID_SIDE + 1, cols: 2 })
+        );
+        assert_eq!(
+            from_grid_string(usize::MAX, usize::MAX, "AB", &Vec::new()),
+            Err(GridError::TooLarge { rows: usize::MAX, cols: usize::MAX })
+        );
+    }
+
+    #[test]
+    fn ragged_rows_are_rejected() {
+        let cells = vec![vec![Cell::Empty; 3], vec![Cell::Empty; 2]];
+
+        assert_eq!(to_grid_string(2, 3, &cells), Err(GridError::RaggedRow { row: 1, expected: 3, actual: 2 }));
+    }
+
+    #[test]
+    fn solution_cell_outside_of_grid_is_rejected() {
+        assert_eq!(
+            from_grid_string(2, 2, "AB#?", &[(1, 0)]),
+            Err(GridError::InvalidSolutionCell { row: 1, col: 0 })
+        );
+        assert_eq!(
+            from_grid_string(2, 2, "AB#?", &[(2, 0)]),
+            Err(GridError::InvalidSolutionCell { row: 2, col: 0 })
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use crate::{Direction, Placement};
+
+// Each hint on the same word reveals a bit more of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HintLevel {
+    FirstLetter,
+    Direction,
+    Placement,
+}
+
+impl HintLevel {
+    pub fn value(&self) -> u8 {
+        match self {
+            HintLevel::FirstLetter => 1,
+            HintLevel::Direction => 2,
+            HintLevel::Placement => 3,
+        }
+    }
+
+    pub fn from_value(value: u8) -> Option<HintLevel> {
+        match value {
+            1 => Some(HintLevel::FirstLetter),
+            2 => Some(HintLevel::Direction),
+            3 => Some(HintLevel::Placement),
+            _ => None
+        }
+    }
+
+    fn next(&self) -> Option<HintLevel> {
+        HintLevel::from_value(self.value() + 1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hint {
+    pub word: String,
+    pub level: HintLevel,
+    // cell of the first letter, given on every level
+    pub row: usize,
+    pub col: usize,
+    pub direction: Option<Direction>,
+    // cell of the last letter, given only with the full placement
+    pub end: Option<(usize, usize)>,
+}
+
+impl Hint {
+    fn reveal(placement: &Placement, level: HintLevel) -> Hint {
+        return Hint {
+            word: placement.word.clone(),
+            level,
+            row: placement.row,
+            col: placement.col,
+            direction: if level >= HintLevel::Direction { Some(placement.direction) } else { None },
+            end: if level == HintLevel::Placement { placement.cells().last().copied() } else { None },
+        };
+    }
+}
+
+// Escalates the previous hint while its word is still not found, otherwise starts over on the first
+// remaining word in reading order. None once every word has been found
+pub fn next_hint(placements: &[Placement], found_words: &[String], previous: Option<(&str, HintLevel)>) -> Option<Hint> {
+    let mut remaining: Vec<&Placement> = placements.iter()
+        .filter(|placement| !found_words.contains(&placement.word))
+        .collect();
+    remaining.sort_by_key(|placement| (placement.row, placement.col));
+
+    if let Some((word, level)) = previous {
+        let placement = remaining.iter().find(|placement| placement.word == word);
+        if let (Some(placement), Some(next_level)) = (placement, level.next()) {
+            return Some(Hint::reveal(placement, next_level));
+        }
+
+        // fully revealed words are skipped while there is anything else left
+        if let Some(placement) = remaining.iter().find(|placement| placement.word != word) {
+            return Some(Hint::reveal(placement, HintLevel::FirstLetter));
+        }
+    }
+
+    return remaining.first().map(|placement| Hint::reveal(placement, HintLevel::FirstLetter));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placements() -> Vec<Placement> {
+        vec![
+            Placement { word: "DOG".to_string(), row: 4, col: 4, direction: Direction::NorthWest, step: 1 },
+            Placement { word: "CAT".to_string(), row: 0, col: 0, direction: Direction::East, step: 0 },
+        ]
+    }
+
+    #[test]
+    fn hints_escalate_on_the_same_word() {
+        let first = next_hint(&placements(), &Vec::new(), None).unwrap();
+        assert_eq!((first.word.as_str(), first.level, first.direction), ("CAT", HintLevel::FirstLetter, None));
+
+        let second = next_hint(&placements(), &Vec::new(), Some(("CAT", first.level))).unwrap();
+        assert_eq!((second.level, second.direction, second.end), (HintLevel::Direction, Some(Direction::East), None));
+
+        let third = next_hint(&placements(), &Vec::new(), Some(("CAT", second.level))).unwrap();
+        assert_eq!((third.level, third.end), (HintLevel::Placement, Some((0, 2))));
+
+        let fourth = next_hint(&placements(), &Vec::new(), Some(("CAT", third.level))).unwrap();
+        assert_eq!((fourth.word.as_str(), fourth.level), ("DOG", HintLevel::FirstLetter));
+    }
+
+    #[test]
+    fn found_words_are_not_hinted() {
+        let found = vec!["CAT".to_string()];
+
+        let hint = next_hint(&placements(), &found, Some(("CAT", HintLevel::FirstLetter))).unwrap();
+        assert_eq!((hint.word.as_str(), hint.level), ("DOG", HintLevel::FirstLetter));
+
+        let found = vec!["CAT".to_string(), "DOG".to_string()];
+        assert_eq!(next_hint(&placements(), &found, None), None);
+    }
+}
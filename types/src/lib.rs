@@ -1,25 +1,232 @@
 use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
 
-pub struct Board {
-    pub rows: usize,
-    pub cols: usize,
-    pub message: String,
-    pub solution: String,
-    pub grid: Vec<Vec<char>>,
-    pub words: HashSet<String>
+pub use crate::grid::{from_grid_string, to_grid_string, GridError};
+pub use crate::hint::{next_hint, Hint, HintLevel};
+pub use crate::puzzle_file::{PuzzleFile, PuzzleFormatError, PuzzleMetadata, PUZZLE_BINARY_MAGIC, PUZZLE_FORMAT_VERSION};
+pub use crate::selection::{Selection, SelectionError};
+
+mod grid;
+mod hint;
+mod puzzle_file;
+mod selection;
+
+// Characters used for the cells without a letter when the grid is written as a string
+pub const EMPTY_CELL: char = '?';
+pub const BLOCKED_CELL: char = '#';
+// Longest side of a board, stored grids larger than that are not decoded
+pub const MAX_GRID_SIDE: usize = 30;
+// Guesses of the solution a player gets on one board
+pub const MAX_SOLUTION_ATTEMPTS: usize = 3;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Cell {
+    // Not decided yet, a finished board has no empty cells
+    Empty,
+    // Outside of the board's shape, never holds a letter
+    Blocked,
+    // Part of at least one hidden word
+    Letter(char),
+    // Left over by the words and spelling the solution
+    Solution(char),
+}
+
+impl Cell {
+    pub fn letter(&self) -> Option<char> {
+        match self {
+            Cell::Letter(letter) | Cell::Solution(letter) => Some(*letter),
+            _ => None
+        }
+    }
+
+    pub fn to_char(&self) -> char {
+        match self {
+            Cell::Empty => EMPTY_CELL,
+            Cell::Blocked => BLOCKED_CELL,
+            Cell::Letter(letter) | Cell::Solution(letter) => *letter,
+        }
+    }
+
+    // Grid strings do not tell word letters from solution letters, see from_grid_string
+    pub fn from_char(c: char) -> Cell {
+        match c {
+            EMPTY_CELL => Cell::Empty,
+            BLOCKED_CELL => Cell::Blocked,
+            letter => Cell::Letter(letter),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    #[serde(rename = "NW")]
+    NorthWest,
+    #[serde(rename = "N")]
+    North,
+    #[serde(rename = "NE")]
+    NorthEast,
+    #[serde(rename = "W")]
+    West,
+    #[serde(rename = "E")]
+    East,
+    #[serde(rename = "SW")]
+    SouthWest,
+    #[serde(rename = "S")]
+    South,
+    #[serde(rename = "SE")]
+    SouthEast,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::NorthWest, Direction::North, Direction::NorthEast, Direction::West,
+        Direction::East, Direction::SouthWest, Direction::South, Direction::SouthEast
+    ];
+
+    // Change of the row index for every letter, north points up
+    pub fn row_step(&self) -> isize {
+        match self {
+            Direction::NorthWest | Direction::North | Direction::NorthEast => -1,
+            Direction::West | Direction::East => 0,
+            Direction::SouthWest | Direction::South | Direction::SouthEast => 1,
+        }
+    }
+
+    pub fn col_step(&self) -> isize {
+        match self {
+            Direction::NorthWest | Direction::West | Direction::SouthWest => -1,
+            Direction::North | Direction::South => 0,
+            Direction::NorthEast | Direction::East | Direction::SouthEast => 1,
+        }
+    }
+
+    pub fn reversed(&self) -> Direction {
+        match self {
+            Direction::NorthWest => Direction::SouthEast,
+            Direction::North => Direction::South,
+            Direction::NorthEast => Direction::SouthWest,
+            Direction::West => Direction::East,
+            Direction::East => Direction::West,
+            Direction::SouthWest => Direction::NorthEast,
+            Direction::South => Direction::North,
+            Direction::SouthEast => Direction::NorthWest,
+        }
+    }
+
+    // Short name stored in the databases, e.g. "NE"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::NorthWest => "NW",
+            Direction::North => "N",
+            Direction::NorthEast => "NE",
+            Direction::West => "W",
+            Direction::East => "E",
+            Direction::SouthWest => "SW",
+            Direction::South => "S",
+            Direction::SouthEast => "SE",
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Direction::ALL.iter().find(|d| d.name().eq_ignore_ascii_case(s)) {
+            Some(direction) => Ok(*direction),
+            None => Err(format!("Direction {} is not supported", s))
+        }
+    }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Placement {
-    pub direction: &'static str,
+    pub word: String,
     pub row: usize,
     pub col: usize,
-    pub word: String,
+    pub direction: Direction,
+    // Number of words placed before this one
     pub step: usize
 }
 
-#[derive(Copy, Clone)]
-pub struct Direction {
-    pub x_dir: isize,
-    pub y_dir: isize,
-    pub dbg_name: &'static str
-}
\ No newline at end of file
+impl Placement {
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        return (0..self.word.chars().count()).map(|depth| (
+            (self.row as isize + self.direction.row_step() * depth as isize) as usize,
+            (self.col as isize + self.direction.col_step() * depth as isize) as usize
+        )).collect();
+    }
+}
+
+pub struct Board {
+    pub rows: usize,
+    pub cols: usize,
+    pub message: String,
+    pub solution: String,
+    pub grid: Vec<Vec<Cell>>,
+    // Hidden words, kept even for boards loaded without their placements
+    pub words: HashSet<String>,
+    pub placements: Vec<Placement>,
+    // (row, col) of every solution letter in the order the message is read
+    pub solution_cells: Vec<(usize, usize)>
+}
+
+impl Board {
+    pub fn is_open(&self, row: usize, col: usize) -> bool {
+        return self.grid[row][col] != Cell::Blocked;
+    }
+
+    pub fn open_cells(&self) -> usize {
+        return self.grid.iter().flatten().filter(|cell| **cell != Cell::Blocked).count();
+    }
+
+    pub fn blocked_cells(&self) -> Vec<(usize, usize)> {
+        let mut cells: Vec<(usize, usize)> = Vec::new();
+
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                if self.grid[r][c] == Cell::Blocked {
+                    cells.push((r, c));
+                }
+            }
+        }
+
+        return cells;
+    }
+
+    // Row major string with one character per cell, the format every crate stores the grid in
+    pub fn to_grid_string(&self) -> Result<String, GridError> {
+        return to_grid_string(self.rows, self.cols, &self.grid);
+    }
+
+    // Letters shared by crossing words, every shared cell counts once per extra word crossing it
+    pub fn overlapping_letters(&self) -> usize {
+        let word_letters: usize = self.words.iter().map(|w| w.chars().count()).sum();
+
+        return word_letters.saturating_sub(self.word_cells());
+    }
+
+    // Average number of words covering a cell that belongs to any word, 1.0 means no crossings at all
+    pub fn density(&self) -> f32 {
+        let word_cells = self.word_cells();
+        if word_cells == 0 {
+            return 0.0;
+        }
+
+        let word_letters: usize = self.words.iter().map(|w| w.chars().count()).sum();
+
+        return word_letters as f32 / word_cells as f32;
+    }
+
+    fn word_cells(&self) -> usize {
+        return self.grid.iter().flatten().filter(|cell| matches!(cell, Cell::Letter(_))).count();
+    }
+}
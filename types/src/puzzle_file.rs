@@ -0,0 +1,231 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use crate::{from_grid_string, Board, GridError, Placement};
+
+// Bumped whenever a field changes meaning or becomes required, readers refuse files newer than they know
+pub const PUZZLE_FORMAT_VERSION: u32 = 1;
+// Binary files start with these bytes and the little endian u32 version, followed by the varint bincode encoded PuzzleFile
+pub const PUZZLE_BINARY_MAGIC: [u8; 4] = *b"CWPZ";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PuzzleFormatError {
+    UnsupportedVersion { version: u32, supported: u32 },
+    InvalidGrid(GridError),
+    InvalidPlacement(String),
+    Malformed(String),
+}
+
+impl Display for PuzzleFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PuzzleFormatError::UnsupportedVersion { version, supported } => write!(
+                f, "Puzzle format version {} is newer than supported version {}", version, supported
+            ),
+            PuzzleFormatError::InvalidGrid(error) => write!(
+                f, "Grid is not valid: {}", error
+            ),
+            PuzzleFormatError::InvalidPlacement(word) => write!(
+                f, "Placement of {} does not match the grid", word
+            ),
+            PuzzleFormatError::Malformed(reason) => write!(
+                f, "Puzzle could not be read: {}", reason
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PuzzleFormatError {}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PuzzleMetadata {
+    pub id: Option<String>,
+    pub created_date: Option<String>,
+    pub created_by: Option<String>,
+    pub seed: Option<u64>,
+    pub difficulty: Option<String>,
+    pub category: Option<String>,
+}
+
+// Self contained puzzle every backend can export and import, fields missing in older files take their defaults
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PuzzleFile {
+    pub version: u32,
+    pub rows: usize,
+    pub cols: usize,
+    // row major, one character per cell as written by Board::to_grid_string
+    pub grid: String,
+    pub message: String,
+    pub solution: String,
+    pub solution_cells: Vec<(usize, usize)>,
+    pub words: Vec<String>,
+    #[serde(default)]
+    pub placements: Vec<Placement>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub metadata: PuzzleMetadata,
+}
+
+impl PuzzleFile {
+    pub fn from_board(board: &Board, language: Option<String>, metadata: PuzzleMetadata) -> Result<PuzzleFile, PuzzleFormatError> {
+        let mut words: Vec<String> = board.words.iter().cloned().collect();
+        words.sort();
+
+        return Ok(PuzzleFile {
+            version: PUZZLE_FORMAT_VERSION,
+            rows: board.rows,
+            cols: board.cols,
+            grid: board.to_grid_string().map_err(PuzzleFormatError::InvalidGrid)?,
+            message: board.message.clone(),
+            solution: board.solution.clone(),
+            solution_cells: board.solution_cells.clone(),
+            words,
+            placements: board.placements.clone(),
+            language,
+            metadata,
+        });
+    }
+
+    pub fn to_board(&self) -> Result<Board, PuzzleFormatError> {
+        let grid = from_grid_string(self.rows, self.cols, &self.grid, &self.solution_cells)
+            .map_err(PuzzleFormatError::InvalidGrid)?;
+
+        for placement in &self.placements {
+            let letters: Vec<Option<char>> = placement.cells().iter()
+                .map(|(row, col)| grid.get(*row).and_then(|r| r.get(*col)).and_then(|cell| cell.letter()))
+                .collect();
+
+            if !placement.word.chars().map(Some).eq(letters) {
+                return Err(PuzzleFormatError::InvalidPlacement(placement.word.clone()));
+            }
+        }
+
+        let mut words: HashSet<String> = self.words.iter().cloned().collect();
+        words.extend(self.placements.iter().map(|p| p.word.clone()));
+
+        return Ok(Board {
+            rows: self.rows,
+            cols: self.cols,
+            message: self.message.clone(),
+            solution: self.solution.clone(),
+            grid,
+            words,
+            placements: self.placements.clone(),
+            solution_cells: self.solution_cells.clone(),
+        });
+    }
+
+    pub fn to_json(&self) -> Result<String, PuzzleFormatError> {
+        return serde_json::to_string(self).map_err(|e| PuzzleFormatError::Malformed(e.to_string()));
+    }
+
+    pub fn from_json(json: &str) -> Result<PuzzleFile, PuzzleFormatError> {
+        // version is read on its own first so newer files fail with a clear error instead of a missing field
+        let header: serde_json::Value = serde_json::from_str(json).map_err(|e| PuzzleFormatError::Malformed(e.to_string()))?;
+        let version = header.get("version").and_then(|v| v.as_u64())
+            .ok_or(PuzzleFormatError::Malformed("missing version".to_string()))?;
+        check_version(u32::try_from(version).unwrap_or(u32::MAX))?;
+
+        return serde_json::from_value(header).map_err(|e| PuzzleFormatError::Malformed(e.to_string()));
+    }
+
+    pub fn to_binary(&self) -> Result<Vec<u8>, PuzzleFormatError> {
+        let mut bytes: Vec<u8> = PUZZLE_BINARY_MAGIC.to_vec();
+        bytes.extend(self.version.to_le_bytes());
+
+        let payload = bincode::DefaultOptions::new().serialize(self).map_err(|e| PuzzleFormatError::Malformed(e.to_string()))?;
+        bytes.extend(payload);
+
+        return Ok(bytes);
+    }
+
+    pub fn from_binary(bytes: &[u8]) -> Result<PuzzleFile, PuzzleFormatError> {
+        if bytes.len() < 8 || bytes[0..4] != PUZZLE_BINARY_MAGIC {
+            return Err(PuzzleFormatError::Malformed("not a puzzle file".to_string()));
+        }
+
+        let version = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        check_version(version)?;
+
+        return bincode::DefaultOptions::new().deserialize(&bytes[8..]).map_err(|e| PuzzleFormatError::Malformed(e.to_string()));
+    }
+}
+
+fn check_version(version: u32) -> Result<(), PuzzleFormatError> {
+    if version > PUZZLE_FORMAT_VERSION {
+        return Err(PuzzleFormatError::UnsupportedVersion { version, supported: PUZZLE_FORMAT_VERSION });
+    }
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Direction;
+    use super::*;
+
+    // CAT and DOG on two rows, the last column holds the solution OK
+    fn puzzle() -> PuzzleFile {
+        return PuzzleFile {
+            version: PUZZLE_FORMAT_VERSION,
+            rows: 2,
+            cols: 4,
+            grid: "CATODOGK".to_string(),
+            message: "ok".to_string(),
+            solution: "OK".to_string(),
+            solution_cells: vec![(0, 3), (1, 3)],
+            words: vec!["CAT".to_string(), "DOG".to_string()],
+            placements: vec![
+                Placement { word: "CAT".to_string(), row: 0, col: 0, direction: Direction::East, step: 0 },
+                Placement { word: "DOG".to_string(), row: 1, col: 0, direction: Direction::East, step: 1 },
+            ],
+            language: Some("en".to_string()),
+            metadata: PuzzleMetadata { seed: Some(42), ..PuzzleMetadata::default() },
+        };
+    }
+
+    #[test]
+    fn json_round_trip_keeps_the_puzzle() {
+        let json = puzzle().to_json().unwrap();
+
+        assert_eq!(PuzzleFile::from_json(&json), Ok(puzzle()));
+        assert_eq!(PuzzleFile::from_json(&json).unwrap().to_board().unwrap().solution_cells, vec![(0, 3), (1, 3)]);
+    }
+
+    #[test]
+    fn binary_round_trip_keeps_the_puzzle() {
+        let bytes = puzzle().to_binary().unwrap();
+
+        assert_eq!(bytes[0..4], *b"CWPZ");
+        assert_eq!(bytes[4..8], PUZZLE_FORMAT_VERSION.to_le_bytes());
+        assert_eq!(PuzzleFile::from_binary(&bytes), Ok(puzzle()));
+    }
+
+    #[test]
+    fn bad_magic_bytes_are_rejected() {
+        let mut bytes = puzzle().to_binary().unwrap();
+        bytes[0..4].copy_from_slice(b"ZIP!");
+
+        assert!(matches!(PuzzleFile::from_binary(&bytes), Err(PuzzleFormatError::Malformed(_))));
+        assert!(matches!(PuzzleFile::from_binary(b"CWP"), Err(PuzzleFormatError::Malformed(_))));
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let newer = PuzzleFile { version: PUZZLE_FORMAT_VERSION + 1, ..puzzle() };
+        let unsupported = Err(PuzzleFormatError::UnsupportedVersion { version: PUZZLE_FORMAT_VERSION + 1, supported: PUZZLE_FORMAT_VERSION });
+
+        assert_eq!(PuzzleFile::from_json(&newer.to_json().unwrap()), unsupported);
+        assert_eq!(PuzzleFile::from_binary(&newer.to_binary().unwrap()), unsupported);
+    }
+
+    #[test]
+    fn version_beyond_u32_is_not_truncated() {
+        let json = puzzle().to_json().unwrap().replace(&format!("\"version\":{}", PUZZLE_FORMAT_VERSION), "\"version\":4294967297");
+
+        assert!(matches!(PuzzleFile::from_json(&json), Err(PuzzleFormatError::UnsupportedVersion { .. })));
+    }
+}
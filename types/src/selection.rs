@@ -0,0 +1,104 @@
+use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+use crate::{Direction, Placement};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectionError {
+    // start and end are not on one row, column or diagonal
+    NotStraight,
+    NoMatchingWord,
+}
+
+impl Display for SelectionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectionError::NotStraight => write!(f, "Selection is not a straight line"),
+            SelectionError::NoMatchingWord => write!(f, "Selection does not match any hidden word"),
+        }
+    }
+}
+
+impl std::error::Error for SelectionError {}
+
+// Cells of the first and the last letter the player marked on the grid
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Selection {
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+}
+
+impl Selection {
+    // Direction from start to end and number of selected cells
+    pub fn direction(&self) -> Result<(Direction, usize), SelectionError> {
+        let row_delta = self.end_row as isize - self.start_row as isize;
+        let col_delta = self.end_col as isize - self.start_col as isize;
+
+        if (row_delta == 0 && col_delta == 0) || (row_delta != 0 && col_delta != 0 && row_delta.abs() != col_delta.abs()) {
+            return Err(SelectionError::NotStraight);
+        }
+
+        let length = row_delta.abs().max(col_delta.abs()) as usize + 1;
+        let direction = Direction::ALL.iter()
+            .find(|d| d.row_step() == row_delta.signum() && d.col_step() == col_delta.signum())
+            .ok_or(SelectionError::NotStraight)?;
+
+        return Ok((*direction, length));
+    }
+
+    // Placement covering exactly the selected cells, read from either end
+    pub fn find_placement<'a>(&self, placements: &'a [Placement]) -> Result<&'a Placement, SelectionError> {
+        let (direction, length) = self.direction()?;
+
+        return placements.iter()
+            .find(|placement| {
+                let same_length = placement.word.chars().count() == length;
+                let forward = placement.row == self.start_row && placement.col == self.start_col && placement.direction == direction;
+                let reversed = placement.row == self.end_row && placement.col == self.end_col && placement.direction == direction.reversed();
+
+                same_length && (forward || reversed)
+            })
+            .ok_or(SelectionError::NoMatchingWord);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placements() -> Vec<Placement> {
+        vec![
+            Placement { word: "CAT".to_string(), row: 0, col: 0, direction: Direction::East, step: 0 },
+            Placement { word: "DOG".to_string(), row: 4, col: 4, direction: Direction::NorthWest, step: 1 },
+        ]
+    }
+
+    #[test]
+    fn forward_selection_matches() {
+        let selection = Selection { start_row: 0, start_col: 0, end_row: 0, end_col: 2 };
+
+        assert_eq!(selection.find_placement(&placements()).unwrap().word, "CAT");
+    }
+
+    #[test]
+    fn reversed_selection_matches() {
+        let selection = Selection { start_row: 2, start_col: 2, end_row: 4, end_col: 4 };
+
+        assert_eq!(selection.find_placement(&placements()).unwrap().word, "DOG");
+    }
+
+    #[test]
+    fn bent_selection_is_rejected() {
+        let selection = Selection { start_row: 0, start_col: 0, end_row: 1, end_col: 2 };
+
+        assert_eq!(selection.find_placement(&placements()), Err(SelectionError::NotStraight));
+    }
+
+    #[test]
+    fn partial_selection_is_rejected() {
+        let selection = Selection { start_row: 0, start_col: 0, end_row: 0, end_col: 1 };
+
+        assert_eq!(selection.find_placement(&placements()), Err(SelectionError::NoMatchingWord));
+    }
+}